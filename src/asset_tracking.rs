@@ -1,69 +1,220 @@
 //! A high-level way to load collections of asset handles as resources.
+//!
+//! Resources are loaded in groups, so a game's loading screen only waits for
+//! the assets that game needs. [`ResourceHandles`] reports progress and
+//! failures for each group, and can retry the assets that failed.
 
-use std::collections::VecDeque;
+use bevy::{
+    asset::{AssetPath, LoadState, UntypedAssetId},
+    prelude::*,
+};
 
-use bevy::prelude::*;
+use crate::games::registry::GameId;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
     app.add_systems(PreUpdate, load_resource_assets);
 }
 
+/// Which part of the app needs a resource.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AssetGroup {
+    /// Needed everywhere, e.g. themes and music.
+    Common,
+    /// Needed only to play one game.
+    Game(GameId),
+}
+
+impl AssetGroup {
+    /// Returns true if a loading screen for `game` should wait for this group.
+    fn is_needed_for(self, game: GameId) -> bool {
+        match self {
+            AssetGroup::Common => true,
+            AssetGroup::Game(group_game) => group_game == game,
+        }
+    }
+}
+
 pub trait LoadResource {
     /// This will load the [`Resource`] as an [`Asset`]. When all of its asset dependencies
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
     /// exists when the assets are ready.
-    fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
+    fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self {
+        self.load_resource_in::<T>(AssetGroup::Common)
+    }
+
+    /// Like [`load_resource`](LoadResource::load_resource), for a resource that only `group` needs.
+    fn load_resource_in<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        group: AssetGroup,
+    ) -> &mut Self;
 }
 
 impl LoadResource for App {
-    fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self {
+    fn load_resource_in<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        group: AssetGroup,
+    ) -> &mut Self {
         self.init_asset::<T>();
         let world = self.world_mut();
         let value = T::from_world(world);
+        let mut dependencies = Vec::new();
+        value.visit_dependencies(&mut |id| dependencies.push(Dependency::new(id)));
         let assets = world.resource::<AssetServer>();
         let handle = assets.add(value);
         let mut handles = world.resource_mut::<ResourceHandles>();
-        handles
-            .waiting
-            .push_back((handle.untyped(), |world, handle| {
+        handles.resources.push(TrackedResource {
+            handle: handle.untyped(),
+            insert: |world, handle| {
                 let assets = world.resource::<Assets<T>>();
-                if let Some(value) = assets.get(handle.id().typed::<T>()) {
-                    world.insert_resource(value.clone());
-                }
-            }));
+                let Some(value) = assets.get(handle.id().typed::<T>()).cloned() else {
+                    return false;
+                };
+                world.insert_resource(value);
+                true
+            },
+            group,
+            dependencies,
+            inserted: false,
+        });
         self
     }
 }
 
-/// A function that inserts a loaded resource.
-type InsertLoadedResource = fn(&mut World, &UntypedHandle);
+/// A function that inserts a loaded resource, returning false if it isn't ready.
+type InsertLoadedResource = fn(&mut World, &UntypedHandle) -> bool;
+
+struct TrackedResource {
+    handle: UntypedHandle,
+    insert: InsertLoadedResource,
+    group: AssetGroup,
+    dependencies: Vec<Dependency>,
+    inserted: bool,
+}
+
+/// One asset a resource is waiting for. Only direct dependencies are tracked.
+struct Dependency {
+    id: UntypedAssetId,
+    loaded: bool,
+    /// Why the asset failed to load, if it did.
+    error: Option<String>,
+}
+
+impl Dependency {
+    fn new(id: UntypedAssetId) -> Self {
+        Self {
+            id,
+            loaded: false,
+            error: None,
+        }
+    }
+}
+
+/// How many of a group's assets have loaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub total: usize,
+}
+
+impl LoadProgress {
+    /// The fraction loaded, in `0..=1`. Nothing to load counts as done.
+    pub fn fraction(self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
+}
+
+/// An asset that failed to load.
+#[derive(Clone, Debug)]
+pub struct LoadFailure {
+    pub path: String,
+    pub error: String,
+}
 
 #[derive(Resource, Default)]
 pub struct ResourceHandles {
-    // Use a queue for waiting assets so they can be cycled through and moved to
-    // `finished` one at a time.
-    waiting: VecDeque<(UntypedHandle, InsertLoadedResource)>,
-    finished: Vec<UntypedHandle>,
+    resources: Vec<TrackedResource>,
 }
 
 impl ResourceHandles {
-    /// Returns true if all requested [`Asset`]s have finished loading and are available as [`Resource`]s.
-    pub fn is_all_done(&self) -> bool {
-        self.waiting.is_empty()
+    /// Returns true if all the [`Asset`]s `game` needs have finished loading and are available as [`Resource`]s.
+    pub fn is_done(&self, game: GameId) -> bool {
+        self.needed_for(game).all(|resource| resource.inserted)
+    }
+
+    /// How many of the assets `game` needs have loaded.
+    pub fn progress(&self, game: GameId) -> LoadProgress {
+        let dependencies = self
+            .needed_for(game)
+            .flat_map(|resource| &resource.dependencies);
+        let (loaded, total) = dependencies.fold((0, 0), |(loaded, total), dependency| {
+            (loaded + usize::from(dependency.loaded), total + 1)
+        });
+        LoadProgress { loaded, total }
+    }
+
+    /// The assets `game` needs that failed to load.
+    pub fn failures(&self, game: GameId, assets: &AssetServer) -> Vec<LoadFailure> {
+        self.needed_for(game)
+            .flat_map(|resource| &resource.dependencies)
+            .filter_map(|dependency| {
+                let error = dependency.error.clone()?;
+                let path = assets
+                    .get_path(dependency.id)
+                    .map_or_else(|| "an asset".to_string(), |path| path.to_string());
+                Some(LoadFailure { path, error })
+            })
+            .collect()
+    }
+
+    /// Tries loading the assets that failed again.
+    pub fn retry_failed(&mut self, assets: &AssetServer) {
+        for dependency in self
+            .resources
+            .iter_mut()
+            .flat_map(|resource| &mut resource.dependencies)
+            .filter(|dependency| dependency.error.is_some())
+        {
+            if let Some(path) = assets.get_path(dependency.id).map(AssetPath::into_owned) {
+                dependency.error = None;
+                assets.reload(path);
+            }
+        }
+    }
+
+    fn needed_for(&self, game: GameId) -> impl Iterator<Item = &TrackedResource> {
+        self.resources
+            .iter()
+            .filter(move |resource| resource.group.is_needed_for(game))
     }
 }
 
 fn load_resource_assets(world: &mut World) {
     world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
         world.resource_scope(|world, assets: Mut<AssetServer>| {
-            for _ in 0..resource_handles.waiting.len() {
-                let (handle, insert_fn) = resource_handles.waiting.pop_front().unwrap();
-                if assets.is_loaded_with_dependencies(&handle) {
-                    insert_fn(world, &handle);
-                    resource_handles.finished.push(handle);
-                } else {
-                    resource_handles.waiting.push_back((handle, insert_fn));
+            for resource in &mut resource_handles.resources {
+                if resource.inserted {
+                    continue;
+                }
+                for dependency in &mut resource.dependencies {
+                    match assets.load_state(dependency.id) {
+                        LoadState::Loaded => dependency.loaded = true,
+                        LoadState::Failed(error) => dependency.error = Some(error.to_string()),
+                        LoadState::NotLoaded | LoadState::Loading => {}
+                    }
+                }
+                // Checked directly rather than with `is_loaded_with_dependencies`,
+                // so a resource still gets inserted once a failed dependency is retried.
+                if resource
+                    .dependencies
+                    .iter()
+                    .all(|dependency| dependency.loaded)
+                {
+                    resource.inserted = (resource.insert)(world, &resource.handle);
                 }
             }
         });
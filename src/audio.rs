@@ -0,0 +1,135 @@
+//! Music and sound effects, and the volume mixer that controls them.
+//!
+//! Spawn audio with [`music`] or [`sound_effect`] so the mixer's volumes apply.
+//! Music is a playlist that plays through once the tracks have loaded.
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::asset_tracking::LoadResource;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AudioSettings>();
+    app.init_resource::<NowPlaying>();
+    app.load_resource::<MusicPlaylist>();
+    app.add_systems(
+        Update,
+        (
+            play_next_track.run_if(resource_exists::<MusicPlaylist>),
+            apply_volume.run_if(resource_changed::<AudioSettings>),
+        ),
+    );
+}
+
+/// Volumes from 0 (silent) to 1 (full).
+#[derive(Resource, Debug)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sound_effects: f32,
+    pub music: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            sound_effects: 1.0,
+            music: 0.5,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    fn volume(&self, channel: f32) -> Volume {
+        if self.muted {
+            Volume::SILENT
+        } else {
+            Volume::Linear(self.master * channel)
+        }
+    }
+}
+
+/// Marks a music track, so its volume follows [`AudioSettings::music`].
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Music;
+
+/// Marks a sound effect, so its volume follows [`AudioSettings::sound_effects`].
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SoundEffect;
+
+/// A music track that plays once and despawns, for the playlist to move on.
+pub fn music(handle: Handle<AudioSource>, settings: &AudioSettings) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.with_volume(settings.volume(settings.music)),
+        Music,
+    )
+}
+
+/// A sound effect that despawns once it has played.
+pub fn sound_effect(handle: Handle<AudioSource>, settings: &AudioSettings) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.with_volume(settings.volume(settings.sound_effects)),
+        SoundEffect,
+    )
+}
+
+fn apply_volume(
+    settings: Res<AudioSettings>,
+    mut music: Query<&mut AudioSink, (With<Music>, Without<SoundEffect>)>,
+    mut sound_effects: Query<&mut AudioSink, With<SoundEffect>>,
+) {
+    for mut sink in &mut music {
+        sink.set_volume(settings.volume(settings.music));
+    }
+    for mut sink in &mut sound_effects {
+        sink.set_volume(settings.volume(settings.sound_effects));
+    }
+}
+
+/// The background music, played in order and then from the top again.
+#[derive(Resource, Asset, TypePath, Clone)]
+pub struct MusicPlaylist {
+    #[dependency]
+    tracks: Vec<Handle<AudioSource>>,
+}
+
+impl FromWorld for MusicPlaylist {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            tracks: ["audio/music/tide.wav", "audio/music/embers.wav"]
+                .map(|path| assets.load(path))
+                .to_vec(),
+        }
+    }
+}
+
+/// The index in the [`MusicPlaylist`] of the next track to play.
+#[derive(Resource, Default)]
+struct NowPlaying {
+    next_track: usize,
+}
+
+/// Starts the next track whenever nothing is playing.
+fn play_next_track(
+    mut commands: Commands,
+    playlist: Res<MusicPlaylist>,
+    settings: Res<AudioSettings>,
+    mut now_playing: ResMut<NowPlaying>,
+    playing: Query<(), With<Music>>,
+) {
+    if !playing.is_empty() || playlist.tracks.is_empty() {
+        return;
+    }
+    let index = now_playing.next_track % playlist.tracks.len();
+    commands.spawn((
+        Name::new("Music"),
+        music(playlist.tracks[index].clone(), &settings),
+    ));
+    now_playing.next_track = index + 1;
+}
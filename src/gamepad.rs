@@ -0,0 +1,110 @@
+//! Gamepad support shared by the menus and the games.
+//!
+//! Gamepads get seats in the order they connect, so in hot-seat games each
+//! controller can drive its own side.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::AppSystems;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GamepadSeats>();
+    app.add_message::<GamepadNavigate>();
+    app.add_systems(
+        Update,
+        (update_gamepad_seats, record_gamepad_navigation)
+            .chain()
+            .in_set(AppSystems::RecordInput),
+    );
+}
+
+/// How far the left stick must be pushed to count as a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Connected gamepads, in the order they were connected.
+#[derive(Resource, Default)]
+pub struct GamepadSeats(Vec<Entity>);
+
+impl GamepadSeats {
+    /// Returns the seat (zero-based player slot) of `gamepad`.
+    /// With fewer than two gamepads connected there are no seats, and the
+    /// one gamepad may play for anyone.
+    pub fn seat(&self, gamepad: Entity) -> Option<usize> {
+        if self.0.len() < 2 {
+            return None;
+        }
+        self.0.iter().position(|&g| g == gamepad)
+    }
+}
+
+/// A direction newly pressed on a gamepad's D-pad or left stick.
+/// `direction` is a unit vector along one axis, with +Y pointing up.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct GamepadNavigate {
+    pub gamepad: Entity,
+    pub direction: IVec2,
+}
+
+/// A run condition that is true when `button` was just pressed on any gamepad.
+pub fn gamepad_just_pressed(button: GamepadButton) -> impl FnMut(Query<&Gamepad>) -> bool + Clone {
+    move |gamepads: Query<&Gamepad>| gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+}
+
+fn update_gamepad_seats(gamepads: Query<Entity, With<Gamepad>>, mut seats: ResMut<GamepadSeats>) {
+    let seats = seats.bypass_change_detection();
+    seats.0.retain(|&gamepad| gamepads.contains(gamepad));
+    for gamepad in &gamepads {
+        if !seats.0.contains(&gamepad) {
+            seats.0.push(gamepad);
+        }
+    }
+}
+
+fn record_gamepad_navigation(
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut held_stick: Local<HashMap<Entity, IVec2>>,
+    mut navigate: MessageWriter<GamepadNavigate>,
+) {
+    held_stick.retain(|&entity, _| gamepads.contains(entity));
+
+    for (entity, gamepad) in &gamepads {
+        // The stick only counts when it is pushed into a new direction, so
+        // holding it doesn't fire every frame.
+        let stick = stick_direction(gamepad.left_stick());
+        let previous = held_stick.insert(entity, stick).unwrap_or(IVec2::ZERO);
+        let mut direction = if stick != previous {
+            stick
+        } else {
+            IVec2::ZERO
+        };
+
+        for (button, button_direction) in [
+            (GamepadButton::DPadUp, IVec2::Y),
+            (GamepadButton::DPadDown, IVec2::NEG_Y),
+            (GamepadButton::DPadLeft, IVec2::NEG_X),
+            (GamepadButton::DPadRight, IVec2::X),
+        ] {
+            if gamepad.just_pressed(button) {
+                direction = button_direction;
+            }
+        }
+
+        if direction != IVec2::ZERO {
+            navigate.write(GamepadNavigate {
+                gamepad: entity,
+                direction,
+            });
+        }
+    }
+}
+
+/// Snaps a stick position to the nearest axis direction, or zero inside the dead zone.
+fn stick_direction(stick: Vec2) -> IVec2 {
+    if stick.length() < STICK_THRESHOLD {
+        IVec2::ZERO
+    } else if stick.x.abs() > stick.y.abs() {
+        IVec2::new(stick.x.signum() as i32, 0)
+    } else {
+        IVec2::new(0, stick.y.signum() as i32)
+    }
+}
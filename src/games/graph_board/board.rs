@@ -0,0 +1,340 @@
+//! Drawing the board and moving its pieces.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    games::two_player::{
+        board::{blended, set_material_color, LINE_WIDTH, NODE_RADIUS, PIECE_RADIUS, RING_RADII},
+        Blocked, Match, MoveCompleted, MoveRedone, MoveUndone,
+    },
+    screens::{gameplay::BoardBounds, InGame, Screen},
+    theme::{
+        motion::{AnimationSettings, ReducedMotion},
+        themes::Theme,
+    },
+};
+
+use super::{
+    definition::Occupancy, input::Selection, is_playing_graph_board, BoardDefinition,
+    BoardDefinitions, GraphBoard, Side,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<GraphMoveEvent>();
+    app.add_systems(OnEnter(InGame), spawn_board.run_if(is_playing_graph_board));
+    app.add_systems(
+        Update,
+        (
+            start_moves,
+            slide_pieces,
+            replay_history_moves,
+            update_colors,
+        )
+            .chain()
+            .run_if(is_playing_graph_board.and(resource_exists::<ActiveBoard>)),
+    );
+}
+
+/// Distance between the furthest-apart nodes, in world units.
+const BOARD_SIZE: f32 = 400.0;
+/// Space kept around the board when it is scaled to fit the window.
+const BOARD_MARGIN: f32 = 20.0;
+
+/// The definition being played, and where its nodes are drawn.
+#[derive(Resource)]
+pub struct ActiveBoard {
+    pub definition: BoardDefinition,
+    /// Each node's position in world units.
+    pub positions: Vec<Vec2>,
+}
+
+impl ActiveBoard {
+    pub(super) fn new(definition: BoardDefinition) -> Self {
+        let min = definition.nodes.iter().copied().reduce(Vec2::min);
+        let max = definition.nodes.iter().copied().reduce(Vec2::max);
+        let (min, max) = min.zip(max).unwrap_or_default();
+        let scale = BOARD_SIZE / (max - min).max_element().max(f32::EPSILON);
+        let center = (min + max) / 2.0;
+        let positions = definition
+            .nodes
+            .iter()
+            .map(|&node| (node - center) * scale)
+            .collect();
+        Self {
+            definition,
+            positions,
+        }
+    }
+
+    /// What stands on each node.
+    pub fn occupancy<'a>(&self, pieces: impl IntoIterator<Item = &'a GraphPiece>) -> Occupancy {
+        let mut occupancy = vec![None; self.positions.len()];
+        for piece in pieces {
+            occupancy[piece.node] = Some(piece.side);
+        }
+        occupancy
+    }
+}
+
+#[derive(Component)]
+pub struct GraphNode {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct GraphPiece {
+    pub side: Side,
+    pub node: usize,
+}
+
+/// The ring around a piece, lit when it can move.
+#[derive(Component)]
+struct PieceRing;
+
+/// Starts moving a piece to a node.
+#[derive(Message)]
+pub struct GraphMoveEvent {
+    pub piece: Entity,
+    pub to: usize,
+}
+
+/// A piece on its way between nodes.
+#[derive(Component)]
+struct Sliding {
+    from: usize,
+    to: usize,
+    start: Vec2,
+    end: Vec2,
+    progress: f32,
+}
+
+fn spawn_board(
+    mut commands: Commands,
+    screen: Res<State<Screen>>,
+    definitions: Res<BoardDefinitions>,
+    definition_assets: Res<Assets<BoardDefinition>>,
+    theme: Res<Theme>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut bounds: ResMut<BoardBounds>,
+) {
+    let Some(definition) = definitions.playing(screen.get(), &definition_assets) else {
+        return;
+    };
+    let board = ActiveBoard::new(definition.clone());
+    let size = board
+        .positions
+        .iter()
+        .fold(Vec2::ZERO, |size, position| size.max(position.abs() * 2.0));
+    bounds.0 = Some(size + Vec2::splat(2.0 * (NODE_RADIUS + BOARD_MARGIN)));
+
+    let node_mesh = meshes.add(Circle::new(NODE_RADIUS));
+    let piece_mesh = meshes.add(Circle::new(PIECE_RADIUS));
+    let ring_mesh = meshes.add(Annulus::new(RING_RADII.0, RING_RADII.1));
+    let line_mesh = meshes.add(Rectangle::new(1.0, LINE_WIDTH));
+    let line_material = materials.add(theme.board.line);
+    let fills = [
+        materials.add(theme.board.white_piece),
+        materials.add(theme.board.black_piece),
+    ];
+
+    let lines: Vec<_> = board
+        .definition
+        .edges
+        .iter()
+        .map(|&(a, b)| (board.positions[a], board.positions[b]))
+        .collect();
+    let nodes: Vec<_> = board
+        .positions
+        .iter()
+        .map(|&position| (position, materials.add(theme.board.node)))
+        .collect();
+    let pieces: Vec<_> = board
+        .definition
+        .start
+        .iter()
+        .map(|&(node, side)| {
+            let ring = materials.add(blended(Color::NONE));
+            (node, side, board.positions[node], ring)
+        })
+        .collect();
+
+    commands.spawn((
+        Name::new(board.definition.name.clone()),
+        Transform::default(),
+        Visibility::default(),
+        StateScoped(screen.get().clone()),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for (start, end) in lines {
+                let middle = (start + end) / 2.0;
+                parent.spawn((
+                    Name::new("Edge"),
+                    Mesh2d(line_mesh.clone()),
+                    MeshMaterial2d(line_material.clone()),
+                    Transform::from_translation(middle.extend(0.0))
+                        .with_rotation(Quat::from_rotation_z((end - start).to_angle()))
+                        .with_scale(Vec3::new(start.distance(end), 1.0, 1.0)),
+                ));
+            }
+            for (index, (position, material)) in nodes.into_iter().enumerate() {
+                parent.spawn((
+                    Name::new(format!("Node {index}")),
+                    GraphNode { index },
+                    Mesh2d(node_mesh.clone()),
+                    MeshMaterial2d(material),
+                    Transform::from_translation(position.extend(1.0)),
+                    Pickable::default(),
+                ));
+            }
+            for (node, side, position, ring) in pieces {
+                let fill = fills[usize::from(side == Side::Black)].clone();
+                parent.spawn((
+                    Name::new(format!("{side:?} Piece")),
+                    GraphPiece { side, node },
+                    Mesh2d(piece_mesh.clone()),
+                    MeshMaterial2d(fill),
+                    Transform::from_translation(position.extend(2.0)),
+                    Pickable::default(),
+                    children![(
+                        Name::new("Piece Ring"),
+                        PieceRing,
+                        Mesh2d(ring_mesh.clone()),
+                        MeshMaterial2d(ring),
+                        Transform::from_xyz(0.0, 0.0, -0.1),
+                        Pickable::IGNORE,
+                    )],
+                ));
+            }
+        })),
+    ));
+    commands.insert_resource(board);
+}
+
+fn start_moves(
+    mut commands: Commands,
+    mut moves: MessageReader<GraphMoveEvent>,
+    board: Res<ActiveBoard>,
+    mut pieces: Query<(&mut GraphPiece, &Transform)>,
+    mut state: ResMut<Match<GraphBoard>>,
+) {
+    for event in moves.read() {
+        let Ok((mut piece, transform)) = pieces.get_mut(event.piece) else {
+            continue;
+        };
+        state.start_move();
+        commands.entity(event.piece).insert(Sliding {
+            from: piece.node,
+            to: event.to,
+            start: transform.translation.truncate(),
+            end: board.positions[event.to],
+            progress: 0.0,
+        });
+        piece.node = event.to;
+    }
+}
+
+fn slide_pieces(
+    mut commands: Commands,
+    time: Res<Time>,
+    animation: Res<AnimationSettings>,
+    reduced_motion: Res<ReducedMotion>,
+    board: Res<ActiveBoard>,
+    mut sliding: Query<(Entity, &GraphPiece, &mut Sliding, &mut Transform)>,
+    pieces: Query<&GraphPiece>,
+    mut completed: MessageWriter<MoveCompleted<GraphBoard>>,
+) {
+    for (entity, piece, mut slide, mut transform) in &mut sliding {
+        let t = animation.advance(&mut slide.progress, time.delta_secs(), &reduced_motion);
+        transform.translation = slide.start.lerp(slide.end, t).extend(2.0);
+
+        if slide.progress >= 1.0 {
+            commands.entity(entity).remove::<Sliding>();
+            let occupancy = board.occupancy(pieces.iter());
+            let definition = &board.definition;
+            completed.write(MoveCompleted {
+                mv: (slide.from, slide.to),
+                blocked: if definition
+                    .moves(&occupancy, piece.side.opposite())
+                    .is_empty()
+                {
+                    Blocked::OpponentLoses
+                } else {
+                    Blocked::No
+                },
+                goal_reached: definition.reached_goal(&occupancy, piece.side),
+            });
+        }
+    }
+}
+
+/// Puts pieces straight back, or forward, for moves that were undone or redone.
+fn replay_history_moves(
+    mut undone: MessageReader<MoveUndone<GraphBoard>>,
+    mut redone: MessageReader<MoveRedone<GraphBoard>>,
+    board: Res<ActiveBoard>,
+    mut pieces: Query<(&mut GraphPiece, &mut Transform)>,
+) {
+    let undone = undone.read().map(|event| (event.mv.1, event.mv.0));
+    let redone = redone.read().map(|event| event.mv);
+    for (from, to) in undone.chain(redone) {
+        if let Some((mut piece, mut transform)) =
+            pieces.iter_mut().find(|(piece, _)| piece.node == from)
+        {
+            piece.node = to;
+            transform.translation = board.positions[to].extend(2.0);
+        }
+    }
+}
+
+/// Lights the rings of pieces that can move, the selected piece, and the nodes
+/// it can move to.
+fn update_colors(
+    theme: Res<Theme>,
+    state: Res<Match<GraphBoard>>,
+    board: Res<ActiveBoard>,
+    selection: Res<Selection>,
+    pieces: Query<(Entity, &GraphPiece, &Children)>,
+    rings: Query<&MeshMaterial2d<ColorMaterial>, With<PieceRing>>,
+    nodes: Query<(&GraphNode, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let occupancy = board.occupancy(pieces.iter().map(|(_, piece, _)| piece));
+    let can_move = !state.is_over() && !state.is_moving();
+    let selected = selection
+        .piece
+        .and_then(|entity| pieces.get(entity).ok())
+        .map(|(_, piece, _)| piece.node);
+    let destinations = selected
+        .map(|node| board.definition.destinations(&occupancy, node))
+        .unwrap_or_default();
+
+    for (entity, piece, children) in &pieces {
+        let color = if selection.piece == Some(entity) {
+            theme.board.highlight_hovered
+        } else if can_move
+            && piece.side == state.turn()
+            && !board
+                .definition
+                .destinations(&occupancy, piece.node)
+                .is_empty()
+        {
+            theme.board.highlight
+        } else {
+            Color::NONE
+        };
+        for ring in rings.iter_many(children) {
+            set_material_color(&mut materials, &ring.0, color);
+        }
+    }
+
+    for (node, material) in &nodes {
+        let color = if selection.destination == Some(node.index) {
+            theme.board.focus_ring
+        } else if destinations.contains(&node.index) {
+            theme.board.node_destination
+        } else {
+            theme.board.node
+        };
+        set_material_color(&mut materials, &material.0, color);
+    }
+}
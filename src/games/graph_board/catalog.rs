@@ -0,0 +1,133 @@
+//! Finding the board files and adding their games to the menus.
+//!
+//! Web builds can't list the files in a folder, so the catalog in
+//! `assets/boards` names them. Each board's game id is its file name after
+//! [`ID_PREFIX`], e.g. `boards/pong_hau_ki`, and its name in the menus is the
+//! one in its definition.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::games::{
+    registry::{Game, GameId, GameRegistry},
+    two_player,
+};
+
+use super::{BoardDefinition, BoardDefinitions, ID_PREFIX};
+
+const CATALOG_PATH: &str = "boards/catalog.boards.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<BoardCatalog>();
+    app.init_asset_loader::<BoardCatalogLoader>();
+    app.init_resource::<PendingCatalog>();
+    app.add_systems(
+        Update,
+        register_boards.run_if(resource_exists::<PendingCatalog>),
+    );
+}
+
+/// The boards listed in the catalog, in the order the game select menu lists them.
+#[derive(Asset, TypePath)]
+struct BoardCatalog {
+    #[dependency]
+    boards: Vec<Handle<BoardDefinition>>,
+}
+
+/// The catalog as written, with board files named relative to it.
+#[derive(Deserialize)]
+struct CatalogFile {
+    boards: Vec<String>,
+}
+
+#[derive(Default, TypePath)]
+struct BoardCatalogLoader;
+
+impl AssetLoader for BoardCatalogLoader {
+    type Asset = BoardCatalog;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<BoardCatalog, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: CatalogFile = ron::de::from_bytes(&bytes)?;
+        let mut boards = Vec::new();
+        for name in &file.boards {
+            let path = load_context.asset_path().resolve_embed(name)?;
+            boards.push(load_context.load(path));
+        }
+        Ok(BoardCatalog { boards })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["boards.ron"]
+    }
+}
+
+/// The catalog, until its boards have been added to the menus.
+#[derive(Resource)]
+struct PendingCatalog(Handle<BoardCatalog>);
+
+impl FromWorld for PendingCatalog {
+    fn from_world(world: &mut World) -> Self {
+        Self(world.resource::<AssetServer>().load(CATALOG_PATH))
+    }
+}
+
+/// Adds every board that loaded to the menus, once none are still loading.
+/// Boards that failed to load are left out; the asset server logs why.
+fn register_boards(
+    mut commands: Commands,
+    pending: Res<PendingCatalog>,
+    assets: Res<AssetServer>,
+    catalogs: Res<Assets<BoardCatalog>>,
+    definitions: Res<Assets<BoardDefinition>>,
+    mut registry: ResMut<GameRegistry>,
+    mut boards: ResMut<BoardDefinitions>,
+) {
+    if assets.load_state(&pending.0).is_failed() {
+        commands.remove_resource::<PendingCatalog>();
+        return;
+    }
+    let Some(catalog) = catalogs.get(&pending.0) else {
+        return;
+    };
+    if catalog.boards.iter().any(|board| {
+        let state = assets.load_state(board);
+        !state.is_loaded() && !state.is_failed()
+    }) {
+        return;
+    }
+
+    for handle in &catalog.boards {
+        let (Some(definition), Some(path)) = (definitions.get(handle), handle.path()) else {
+            continue;
+        };
+        // The menus and screens keep games' ids and names for the whole run,
+        // and there are only a handful of boards, so they are leaked.
+        let file_name = path
+            .path()
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let stem = file_name.strip_suffix(".board.ron").unwrap_or(&file_name);
+        let id = GameId(format!("{ID_PREFIX}{stem}").leak());
+        registry.register(Game {
+            id,
+            name: definition.name.clone().leak(),
+            options: two_player::mode_options(),
+            settings: Vec::new(),
+        });
+        boards.0.insert(id, handle.clone());
+    }
+    commands.remove_resource::<PendingCatalog>();
+}
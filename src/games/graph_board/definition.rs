@@ -0,0 +1,234 @@
+//! The board definition file format, and the rules it describes.
+//!
+//! A definition is a `*.board.ron` file: the nodes and the edges between
+//! them, where each side's pieces start, any restrictions on moves, and how the
+//! game is won. Pieces move one edge at a time onto an empty node.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use super::Side;
+
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+pub struct BoardDefinition {
+    pub name: String,
+    /// Node positions. The board is scaled to fit the window, so only their
+    /// positions relative to each other matter. Y points up.
+    pub nodes: Vec<Vec2>,
+    /// Pairs of nodes a piece can move between, either way.
+    pub edges: Vec<(usize, usize)>,
+    /// The node each piece starts on.
+    pub start: Vec<(usize, Side)>,
+    #[serde(default)]
+    pub restrictions: Vec<Restriction>,
+    pub win: WinCondition,
+}
+
+/// A limit on which moves are legal, beyond moving along an edge to an empty node.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Restriction {
+    /// A piece may only move onto or off these nodes while it is next to one
+    /// of the other side's pieces, like Mū Tōrere's pūtahi.
+    NextToOpponent(Vec<usize>),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum WinCondition {
+    /// A side wins when the other has no legal move.
+    BlockOpponent,
+    /// A side wins when its pieces stand on all of its goal nodes, or when
+    /// the other has no legal move.
+    ReachGoal {
+        white: Vec<usize>,
+        black: Vec<usize>,
+    },
+}
+
+/// What stands on each node.
+pub type Occupancy = Vec<Option<Side>>;
+
+impl BoardDefinition {
+    pub fn neighbours(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges.iter().filter_map(move |&(a, b)| {
+            if a == node {
+                Some(b)
+            } else if b == node {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The legal moves for the piece on `from`, as destination nodes.
+    pub fn destinations(&self, occupancy: &[Option<Side>], from: usize) -> Vec<usize> {
+        let Some(side) = occupancy[from] else {
+            return Vec::new();
+        };
+        let next_to_opponent = self
+            .neighbours(from)
+            .any(|node| occupancy[node] == Some(side.opposite()));
+        self.neighbours(from)
+            .filter(|&to| occupancy[to].is_none())
+            .filter(|&to| {
+                self.restrictions
+                    .iter()
+                    .all(|restriction| match restriction {
+                        Restriction::NextToOpponent(nodes) => {
+                            next_to_opponent || !(nodes.contains(&from) || nodes.contains(&to))
+                        }
+                    })
+            })
+            .collect()
+    }
+
+    /// Every legal move for `side`, as (from, to) pairs.
+    pub fn moves(&self, occupancy: &[Option<Side>], side: Side) -> Vec<(usize, usize)> {
+        (0..self.nodes.len())
+            .filter(|&from| occupancy[from] == Some(side))
+            .flat_map(|from| {
+                self.destinations(occupancy, from)
+                    .into_iter()
+                    .map(move |to| (from, to))
+            })
+            .collect()
+    }
+
+    /// Returns true if `side` has met its goal, for boards that have one.
+    pub fn reached_goal(&self, occupancy: &[Option<Side>], side: Side) -> bool {
+        match &self.win {
+            WinCondition::BlockOpponent => false,
+            WinCondition::ReachGoal { white, black } => {
+                let goal = match side {
+                    Side::White => white,
+                    Side::Black => black,
+                };
+                goal.iter().all(|&node| occupancy[node] == Some(side))
+            }
+        }
+    }
+
+    /// Checks that every node the definition mentions exists.
+    fn validate(&self) -> Result<(), String> {
+        let count = self.nodes.len();
+        let check = |node: usize, what: &str| {
+            if node < count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{what} uses node {node}, but there are only {count}"
+                ))
+            }
+        };
+        for &(a, b) in &self.edges {
+            check(a, "an edge")?;
+            check(b, "an edge")?;
+        }
+        for (i, &(node, _)) in self.start.iter().enumerate() {
+            check(node, "a start placement")?;
+            if self.start[..i].iter().any(|&(other, _)| other == node) {
+                return Err(format!("two pieces start on node {node}"));
+            }
+        }
+        for Restriction::NextToOpponent(nodes) in &self.restrictions {
+            for &node in nodes {
+                check(node, "a restriction")?;
+            }
+        }
+        if let WinCondition::ReachGoal { white, black } = &self.win {
+            for &node in white.iter().chain(black) {
+                check(node, "a goal")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, TypePath)]
+pub(super) struct BoardDefinitionLoader;
+
+impl AssetLoader for BoardDefinitionLoader {
+    type Asset = BoardDefinition;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<BoardDefinition, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let definition: BoardDefinition = ron::de::from_bytes(&bytes)?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["board.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pong_hau_ki() -> BoardDefinition {
+        ron::de::from_str(include_str!("../../../assets/boards/pong_hau_ki.board.ron")).unwrap()
+    }
+
+    #[test]
+    fn packaged_board_is_valid() {
+        assert_eq!(pong_hau_ki().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_an_edge_to_a_missing_node() {
+        let mut board = pong_hau_ki();
+        board.edges.push((4, 5));
+        assert_eq!(
+            board.validate(),
+            Err("an edge uses node 5, but there are only 5".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_a_piece_off_the_board() {
+        let mut board = pong_hau_ki();
+        board.start[0].0 = 7;
+        assert!(board.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_two_pieces_on_one_node() {
+        let mut board = pong_hau_ki();
+        board.start[1].0 = 0;
+        assert_eq!(
+            board.validate(),
+            Err("two pieces start on node 0".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_a_restriction_on_a_missing_node() {
+        let mut board = pong_hau_ki();
+        board
+            .restrictions
+            .push(Restriction::NextToOpponent(vec![2, 9]));
+        assert!(board.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_a_goal_on_a_missing_node() {
+        let mut board = pong_hau_ki();
+        board.win = WinCondition::ReachGoal {
+            white: vec![3, 4],
+            black: vec![0, 5],
+        };
+        assert!(board.validate().is_err());
+    }
+}
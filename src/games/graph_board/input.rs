@@ -0,0 +1,335 @@
+//! Player and computer moves on a graph board.
+//!
+//! Clicking or tapping a piece selects it, and clicking one of the nodes it
+//! can reach moves it there. The keyboard and gamepads drive the same
+//! selection through [`BoardInput`]s.
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    games::two_player::{ComputerTurn, GameMode, Match, TwoPlayerGame},
+    screens::{
+        gameplay::{BoardAction, BoardFocus, BoardInput},
+        InGame,
+    },
+    AppSystems, PausableSystems, Pause,
+};
+
+use super::{
+    board::{ActiveBoard, GraphMoveEvent, GraphNode, GraphPiece},
+    is_playing_graph_board, GraphBoard,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Selection>();
+    app.add_systems(
+        OnEnter(InGame),
+        reset_selection.run_if(is_playing_graph_board),
+    );
+    app.add_systems(
+        Update,
+        (handle_board_actions, computer_turn)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(is_playing_graph_board.and(resource_exists::<ActiveBoard>)),
+    );
+    app.add_observer(click_piece).add_observer(click_node);
+}
+
+/// The selected piece and, when chosen from the keyboard or a gamepad, the
+/// destination focused for it.
+#[derive(Resource, Default)]
+pub(super) struct Selection {
+    pub piece: Option<Entity>,
+    pub destination: Option<usize>,
+}
+
+fn reset_selection(mut selection: ResMut<Selection>) {
+    *selection = Selection::default();
+}
+
+/// The current player's pieces that may move right now, in node order, with
+/// their destinations.
+fn movable_pieces(
+    board: &ActiveBoard,
+    state: &Match<GraphBoard>,
+    pause: &State<Pause>,
+    pieces: &Query<(Entity, &GraphPiece)>,
+) -> Vec<(Entity, usize, Vec<usize>)> {
+    if state.is_over() || state.is_moving() || !state.is_human_turn() || pause.get().0 {
+        return Vec::new();
+    }
+    let occupancy = board.occupancy(pieces.iter().map(|(_, piece)| piece));
+    let mut movable: Vec<_> = pieces
+        .iter()
+        .filter(|(_, piece)| piece.side == state.turn())
+        .filter_map(|(entity, piece)| {
+            let destinations = board.definition.destinations(&occupancy, piece.node);
+            (!destinations.is_empty()).then_some((entity, piece.node, destinations))
+        })
+        .collect();
+    movable.sort_by_key(|&(_, node, _)| node);
+    movable
+}
+
+fn click_piece(
+    click: On<Pointer<Click>>,
+    board: Option<Res<ActiveBoard>>,
+    state: Res<Match<GraphBoard>>,
+    pause: Res<State<Pause>>,
+    pieces: Query<(Entity, &GraphPiece)>,
+    mut selection: ResMut<Selection>,
+) {
+    if click.button != PointerButton::Primary || !pieces.contains(click.entity) {
+        return;
+    }
+    let Some(board) = board else {
+        return;
+    };
+    let movable = movable_pieces(&board, &state, &pause, &pieces);
+    if !movable.iter().any(|&(entity, ..)| entity == click.entity) {
+        return;
+    }
+    // Clicking the selected piece again puts it down.
+    let piece = (selection.piece != Some(click.entity)).then_some(click.entity);
+    *selection = Selection {
+        piece,
+        destination: None,
+    };
+}
+
+fn click_node(
+    click: On<Pointer<Click>>,
+    board: Option<Res<ActiveBoard>>,
+    state: Res<Match<GraphBoard>>,
+    pause: Res<State<Pause>>,
+    nodes: Query<&GraphNode>,
+    pieces: Query<(Entity, &GraphPiece)>,
+    mut selection: ResMut<Selection>,
+    mut moves: MessageWriter<GraphMoveEvent>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    let (Some(board), Ok(node)) = (board, nodes.get(click.entity)) else {
+        return;
+    };
+    // Move the selected piece there or, with nothing selected, the only piece
+    // that can reach it (if there is exactly one).
+    let movable = movable_pieces(&board, &state, &pause, &pieces);
+    let mut candidates = movable.iter().filter(|(entity, _, destinations)| {
+        selection.piece.is_none_or(|selected| selected == *entity)
+            && destinations.contains(&node.index)
+    });
+    if let (Some(&(piece, ..)), None) = (candidates.next(), candidates.next()) {
+        moves.write(GraphMoveEvent {
+            piece,
+            to: node.index,
+        });
+        *selection = Selection::default();
+    }
+}
+
+fn handle_board_actions(
+    mut inputs: MessageReader<BoardInput>,
+    board: Res<ActiveBoard>,
+    state: Res<Match<GraphBoard>>,
+    mode: Res<GameMode>,
+    pause: Res<State<Pause>>,
+    pieces: Query<(Entity, &GraphPiece)>,
+    mut selection: ResMut<Selection>,
+    mut board_focus: ResMut<BoardFocus>,
+    mut moves: MessageWriter<GraphMoveEvent>,
+) {
+    let movable = movable_pieces(&board, &state, &pause, &pieces);
+    // Drop the selection once its piece can no longer move.
+    if selection
+        .piece
+        .is_some_and(|selected| !movable.iter().any(|&(entity, ..)| entity == selected))
+    {
+        *selection = Selection::default();
+    }
+    let destinations_of = |entity: Entity| {
+        movable
+            .iter()
+            .find(|&&(e, ..)| e == entity)
+            .map(|(_, _, destinations)| destinations.as_slice())
+            .unwrap_or_default()
+    };
+
+    for input in inputs.read() {
+        // In hot-seat games each seated gamepad only plays its own side.
+        if let Some(seat) = input.seat
+            && *mode == GameMode::VsPlayer
+            && GraphBoard::SIDES[seat % 2] != state.turn()
+        {
+            continue;
+        }
+
+        match input.action {
+            BoardAction::Next | BoardAction::Previous => {
+                let forward = input.action == BoardAction::Next;
+                match (selection.piece, selection.destination) {
+                    (Some(piece), Some(destination)) => {
+                        selection.destination =
+                            cycle(destinations_of(piece), Some(destination), forward);
+                    }
+                    _ => {
+                        let entities: Vec<Entity> = movable.iter().map(|&(e, ..)| e).collect();
+                        selection.piece = cycle(&entities, selection.piece, forward);
+                    }
+                }
+            }
+            BoardAction::FocusNode(node) => match (selection.piece, selection.destination) {
+                (Some(piece), Some(_)) if destinations_of(piece).contains(&node) => {
+                    selection.destination = Some(node);
+                }
+                _ => {
+                    if let Some(&(entity, ..)) = movable.iter().find(|&&(_, n, _)| n == node) {
+                        selection.piece = Some(entity);
+                        selection.destination = None;
+                    }
+                }
+            },
+            // Boards are free-form graphs, with no node that is always the centre.
+            BoardAction::FocusCenter => {}
+            BoardAction::Confirm => match (selection.piece, selection.destination) {
+                (Some(piece), Some(to)) => {
+                    moves.write(GraphMoveEvent { piece, to });
+                    *selection = Selection::default();
+                }
+                (Some(piece), None) => match destinations_of(piece) {
+                    &[to] => {
+                        moves.write(GraphMoveEvent { piece, to });
+                        *selection = Selection::default();
+                    }
+                    destinations => selection.destination = destinations.first().copied(),
+                },
+                (None, _) => selection.piece = movable.first().map(|&(e, ..)| e),
+            },
+            BoardAction::Cancel => {
+                if selection.destination.is_some() {
+                    selection.destination = None;
+                } else {
+                    selection.piece = None;
+                }
+            }
+        }
+    }
+
+    board_focus.0 = selection.piece.is_some();
+}
+
+/// Returns the item after (or before) `current` in `items`, wrapping around.
+/// With nothing current, starts from the first (or last) item.
+fn cycle<T: Copy + PartialEq>(items: &[T], current: Option<T>, forward: bool) -> Option<T> {
+    if items.is_empty() {
+        return None;
+    }
+    let index = match current.and_then(|c| items.iter().position(|&item| item == c)) {
+        Some(i) if forward => (i + 1) % items.len(),
+        Some(i) => (i + items.len() - 1) % items.len(),
+        None if forward => 0,
+        None => items.len() - 1,
+    };
+    Some(items[index])
+}
+
+/// Plays a move that wins on the spot if there is one, otherwise a random legal move.
+fn computer_turn(
+    mut turns: MessageReader<ComputerTurn<GraphBoard>>,
+    board: Res<ActiveBoard>,
+    pieces: Query<(Entity, &GraphPiece)>,
+    mut moves: MessageWriter<GraphMoveEvent>,
+) {
+    for turn in turns.read() {
+        let occupancy = board.occupancy(pieces.iter().map(|(_, piece)| piece));
+        let definition = &board.definition;
+        let legal = definition.moves(&occupancy, turn.side);
+        let wins = |&(from, to): &(usize, usize)| {
+            let mut after = occupancy.clone();
+            after[to] = after[from].take();
+            definition.reached_goal(&after, turn.side)
+                || definition.moves(&after, turn.side.opposite()).is_empty()
+        };
+        let chosen = legal
+            .iter()
+            .copied()
+            .find(wins)
+            .or_else(|| legal.choose(&mut rand::rng()).copied());
+        let Some((from, to)) = chosen else {
+            continue;
+        };
+        if let Some((piece, _)) = pieces.iter().find(|(_, piece)| piece.node == from) {
+            moves.write(GraphMoveEvent { piece, to });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        camera::NormalizedRenderTarget,
+        picking::{
+            backend::HitData,
+            pointer::{Location, PointerId},
+        },
+    };
+
+    use super::*;
+    use crate::games::graph_board::BoardDefinition;
+
+    fn click(entity: Entity) -> Pointer<Click> {
+        let location = Location {
+            target: NormalizedRenderTarget::None {
+                width: 1,
+                height: 1,
+            },
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+            duration: Duration::ZERO,
+        };
+        Pointer::new(PointerId::Mouse, location, click, entity)
+    }
+
+    #[test]
+    fn clicking_a_node_moves_the_selected_piece_there() {
+        let definition: BoardDefinition =
+            ron::de::from_str(include_str!("../../../assets/boards/pong_hau_ki.board.ron"))
+                .unwrap();
+        let mut app = App::new();
+        app.add_message::<GraphMoveEvent>()
+            .init_resource::<Selection>()
+            .init_resource::<Match<GraphBoard>>()
+            .insert_resource(State::new(Pause(false)))
+            .insert_resource(ActiveBoard::new(definition.clone()))
+            .add_observer(click_piece)
+            .add_observer(click_node);
+        let world = app.world_mut();
+        let pieces: Vec<Entity> = definition
+            .start
+            .iter()
+            .map(|&(node, side)| world.spawn(GraphPiece { side, node }).id())
+            .collect();
+        let center = world.spawn(GraphNode { index: 2 }).id();
+
+        // Both of White's pieces can reach the centre, so one has to be picked first.
+        world.trigger(click(center));
+        world.trigger(click(pieces[0]));
+        world.trigger(click(center));
+
+        let moves: Vec<GraphMoveEvent> = world
+            .resource_mut::<Messages<GraphMoveEvent>>()
+            .drain()
+            .collect();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].piece, pieces[0]);
+        assert_eq!(moves[0].to, 2);
+    }
+}
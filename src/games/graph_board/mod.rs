@@ -0,0 +1,113 @@
+//! A runtime for games played by moving pieces along the edges of a small
+//! graph, defined in data files.
+//!
+//! Each game is a [`BoardDefinition`] in `assets/boards`, listed in the
+//! catalog there. The runtime draws the board, lets people and the computer
+//! move, and plays by the definition's rules, with everything else from the
+//! [two-player framework](crate::games::two_player).
+//!
+//! Mū Tōrere stays a game of its own, although its traditional board could be
+//! written as a definition, with its pūtahi rule as a
+//! [`NextToOpponent`](definition::Restriction::NextToOpponent) restriction.
+//! Its other board sizes, the pūtahi rule that lapses after the opening and
+//! passing when blocked are options a fixed definition can't express, and its
+//! solver, puzzles, tutorial and start editor all work on its own `MoveRules`.
+
+mod board;
+mod catalog;
+mod definition;
+mod input;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+pub use definition::BoardDefinition;
+
+use crate::{
+    games::{
+        registry::GameId,
+        two_player::{self, TwoPlayerGame},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<BoardDefinition>();
+    app.init_asset_loader::<definition::BoardDefinitionLoader>();
+    app.init_resource::<BoardDefinitions>();
+    app.add_plugins((
+        two_player::game_plugin::<GraphBoard>,
+        catalog::plugin,
+        board::plugin,
+        input::plugin,
+    ));
+}
+
+/// The start of the id of every game defined by a board file.
+const ID_PREFIX: &str = "boards/";
+
+/// The definition of every board in the menus, by game id.
+#[derive(Resource, Default)]
+pub struct BoardDefinitions(HashMap<GameId, Handle<BoardDefinition>>);
+
+impl BoardDefinitions {
+    /// The definition of the board being played, if one is.
+    pub fn playing<'a>(
+        &self,
+        screen: &Screen,
+        definitions: &'a Assets<BoardDefinition>,
+    ) -> Option<&'a BoardDefinition> {
+        let Screen::Playing(id) = screen else {
+            return None;
+        };
+        definitions.get(self.0.get(id)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Side {
+    White,
+    Black,
+}
+
+impl Side {
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::White => Side::Black,
+            Side::Black => Side::White,
+        }
+    }
+}
+
+/// The runtime, as far as the two-player framework needs it.
+pub struct GraphBoard;
+
+impl TwoPlayerGame for GraphBoard {
+    type Side = Side;
+    /// The nodes a piece moved from and to.
+    type Move = (usize, usize);
+    /// Each board has one set of rules, written in its definition.
+    type Rules = ();
+
+    const SIDES: [Side; 2] = [Side::White, Side::Black];
+
+    fn plays(id: GameId) -> bool {
+        id.0.starts_with(ID_PREFIX)
+    }
+
+    fn side_name(side: Side) -> &'static str {
+        match side {
+            Side::White => "White",
+            Side::Black => "Black",
+        }
+    }
+
+    fn rules_label(_: ()) -> String {
+        String::new()
+    }
+}
+
+/// Returns true if a board defined in a file is being played.
+pub fn is_playing_graph_board(screen: Res<State<Screen>>) -> bool {
+    two_player::is_playing_game::<GraphBoard>(screen)
+}
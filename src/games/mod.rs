@@ -1,10 +1,14 @@
 //! Individual game implementations.
 //! Each game is a self-contained module with its own plugin.
 
+pub mod graph_board;
 pub mod mu_torere;
+pub mod registry;
+pub mod two_player;
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(mu_torere::plugin);
+    app.init_resource::<registry::GameRegistry>();
+    app.add_plugins((two_player::plugin, mu_torere::plugin, graph_board::plugin));
 }
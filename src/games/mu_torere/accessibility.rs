@@ -0,0 +1,252 @@
+//! Screen reader support for Mu Torere.
+//!
+//! Pieces and board nodes get AccessKit nodes describing what is on them and
+//! where pieces can go, the screen reader's focus follows the board focus,
+//! and each move and the result are announced.
+
+use accesskit::{Node as AccessKitNode, Role};
+use bevy::{a11y::AccessibilityNode, input_focus::InputFocus, prelude::*};
+
+use crate::{
+    games::two_player::{GameOver, MoveRedone, MoveUndone, TurnChanged},
+    theme::accessibility::Announce,
+};
+
+use super::{
+    board::{get_valid_moves, BoardNode, BoardSize, Piece, Rules},
+    input::PieceFocus,
+    is_playing_mu_torere,
+    state::{GameState, MuTorere, PieceColor, PutahiRule},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            (describe_pieces, describe_nodes).run_if(board_changed),
+            follow_board_focus.run_if(resource_changed::<PieceFocus>),
+            announce_turns,
+            announce_history_moves,
+            announce_result,
+        )
+            .run_if(is_playing_mu_torere),
+    );
+}
+
+/// The spoken name of a board node, e.g. "kewai 3" or "pūtahi".
+/// Kewai are numbered from 1, like the keys that focus them.
+pub fn node_name(size: BoardSize, index: usize) -> String {
+    if index == size.center() {
+        "pūtahi".to_string()
+    } else {
+        format!("kewai {}", index + 1)
+    }
+}
+
+fn color_name(color: PieceColor) -> &'static str {
+    match color {
+        PieceColor::White => "White",
+        PieceColor::Black => "Black",
+    }
+}
+
+/// Joins names into a readable list: "a", "a or b", "a, b or c".
+fn name_list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} or {last}", rest.join(", ")),
+    }
+}
+
+/// Sets an entity's accessible name, adding its accessibility node if needed.
+fn set_label(
+    commands: &mut Commands,
+    entity: Entity,
+    node: Option<Mut<AccessibilityNode>>,
+    label: String,
+) {
+    match node {
+        Some(mut node) => {
+            if node.label() != Some(label.as_str()) {
+                node.set_label(label);
+            }
+        }
+        None => {
+            let mut node = AccessKitNode::new(Role::Button);
+            node.set_label(label);
+            commands.entity(entity).insert(AccessibilityNode(node));
+        }
+    }
+}
+
+/// Whether anything the piece and node descriptions mention has changed: a
+/// piece moved or was spawned, or the turn, result or move rules did. The
+/// match itself changes every frame while a clock runs, so only the parts the
+/// descriptions use are compared.
+fn board_changed(
+    game_state: Res<GameState>,
+    rules: Rules,
+    board: Query<(), Or<(Changed<Piece>, Added<BoardNode>)>>,
+    mut described: Local<Option<(PieceColor, bool, usize, BoardSize, PutahiRule)>>,
+) -> bool {
+    let rules = rules.current();
+    let current = Some((
+        game_state.turn(),
+        game_state.is_over(),
+        rules.moves_played,
+        rules.size,
+        rules.putahi,
+    ));
+    let changed = *described != current || !board.is_empty();
+    *described = current;
+    changed
+}
+
+/// Labels pieces like "White piece on kewai 3, can move to pūtahi".
+fn describe_pieces(
+    mut commands: Commands,
+    size: Res<BoardSize>,
+    rules: Rules,
+    game_state: Res<GameState>,
+    mut pieces: Query<(Entity, &Piece, Option<&mut AccessibilityNode>)>,
+    pieces_for_validation: Query<(&Piece, &Children)>,
+) {
+    for (entity, piece, node) in &mut pieces {
+        let mut label = format!(
+            "{} piece on {}",
+            color_name(piece.color),
+            node_name(*size, piece.node_index)
+        );
+        if !game_state.is_over() && piece.color == game_state.turn() {
+            let destinations: Vec<String> =
+                get_valid_moves(rules.current(), piece, &pieces_for_validation)
+                    .into_iter()
+                    .map(|node| node_name(*size, node))
+                    .collect();
+            if destinations.is_empty() {
+                label.push_str(", cannot move");
+            } else {
+                label.push_str(&format!(", can move to {}", name_list(&destinations)));
+            }
+        }
+        set_label(&mut commands, entity, node, label);
+    }
+}
+
+/// Labels nodes like "kewai 5, Black piece" or "pūtahi, empty".
+fn describe_nodes(
+    mut commands: Commands,
+    size: Res<BoardSize>,
+    mut nodes: Query<(Entity, &BoardNode, Option<&mut AccessibilityNode>)>,
+    pieces: Query<&Piece>,
+) {
+    for (entity, node, accessibility_node) in &mut nodes {
+        let contents = pieces
+            .iter()
+            .find(|piece| piece.node_index == node.index)
+            .map_or_else(
+                || "empty".to_string(),
+                |piece| format!("{} piece", color_name(piece.color)),
+            );
+        let label = format!("{}, {contents}", node_name(*size, node.index));
+        set_label(&mut commands, entity, accessibility_node, label);
+    }
+}
+
+/// Moves the screen reader's focus to the focused piece or destination, so it
+/// reads out the description.
+fn follow_board_focus(
+    focus: Res<PieceFocus>,
+    nodes: Query<(Entity, &BoardNode)>,
+    board: Query<(), Or<(With<Piece>, With<BoardNode>)>>,
+    mut input_focus: ResMut<InputFocus>,
+) {
+    let focused = match (focus.piece, focus.destination) {
+        (_, Some(destination)) => nodes
+            .iter()
+            .find(|(_, node)| node.index == destination)
+            .map(|(entity, _)| entity),
+        (piece, None) => piece,
+    };
+    match focused {
+        Some(entity) => input_focus.set(entity),
+        // Leave the focus alone if it's somewhere else, like a menu button.
+        None if input_focus.0.is_some_and(|entity| board.contains(entity)) => {
+            input_focus.clear();
+        }
+        None => {}
+    }
+}
+
+fn announce_turns(
+    mut turn_events: MessageReader<TurnChanged<MuTorere>>,
+    size: Res<BoardSize>,
+    game_state: Res<GameState>,
+    mut announce: MessageWriter<Announce>,
+) {
+    for event in turn_events.read() {
+        let next = color_name(event.side);
+        let text = match game_state.last_move() {
+            // A side that can't move passes, under some rules.
+            Some((from, to)) if game_state.last_mover() == Some(event.side) => format!(
+                "{next} moved from {} to {}. {} can't move, so {next} moves again.",
+                node_name(*size, from),
+                node_name(*size, to),
+                color_name(event.side.opposite()),
+            ),
+            Some((from, to)) => format!(
+                "{} moved from {} to {}. {next} to move.",
+                color_name(event.side.opposite()),
+                node_name(*size, from),
+                node_name(*size, to),
+            ),
+            None => format!("{next} to move."),
+        };
+        announce.write(Announce(text));
+    }
+}
+
+/// Says which moves were taken back or played again, e.g. "Undid White's move
+/// from kewai 3 to pūtahi."
+fn announce_history_moves(
+    mut undone: MessageReader<MoveUndone<MuTorere>>,
+    mut redone: MessageReader<MoveRedone<MuTorere>>,
+    size: Res<BoardSize>,
+    game_state: Res<GameState>,
+    mut announce: MessageWriter<Announce>,
+) {
+    let undone = undone.read().map(|event| ("Undid", event.side, event.mv));
+    let redone = redone.read().map(|event| ("Redid", event.side, event.mv));
+    let mut text: Vec<String> = undone
+        .chain(redone)
+        .map(|(verb, side, (from, to))| {
+            format!(
+                "{verb} {}'s move from {} to {}.",
+                color_name(side),
+                node_name(*size, from),
+                node_name(*size, to)
+            )
+        })
+        .collect();
+    if text.is_empty() {
+        return;
+    }
+    if !game_state.is_over() {
+        text.push(format!("{} to move.", color_name(game_state.turn())));
+    }
+    announce.write(Announce(text.join(" ")));
+}
+
+fn announce_result(
+    mut game_over_events: MessageReader<GameOver<MuTorere>>,
+    mut announce: MessageWriter<Announce>,
+) {
+    for event in game_over_events.read() {
+        announce.write(Announce(format!(
+            "{} {}",
+            event.result.headline::<MuTorere>(),
+            event.result.reason::<MuTorere>(),
+        )));
+    }
+}
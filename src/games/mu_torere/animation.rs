@@ -1,18 +1,30 @@
 //! Piece movement animation for Mu Torere.
 
+use std::f32::consts::{PI, TAU};
+
 use bevy::prelude::*;
 
 use super::{
-    board::{has_any_valid_moves, node_position, Piece},
+    board::{board_nodes, BoardSize, Piece, Rules, OUTER_RADIUS},
     is_playing_mu_torere,
-    state::{GameOverEvent, GameSettings, GameState, TurnChangeEvent},
+    state::{GameSettings, GameState, MuTorere},
+};
+use crate::{
+    games::two_player::MoveCompleted,
+    theme::motion::{AnimationSettings, ReducedMotion},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_message::<MoveEvent>();
     app.add_systems(
         Update,
-        (handle_move_events, animate_pieces, check_animation_complete)
+        (
+            handle_move_events,
+            animate_pieces,
+            animate_returning_pieces,
+            shake_pieces,
+            check_animation_complete,
+        )
             .chain()
             .run_if(is_playing_mu_torere),
     );
@@ -26,42 +38,118 @@ pub struct MoveEvent {
 
 #[derive(Component)]
 pub struct MovingPiece {
+    pub path: MovePath,
+    pub progress: f32,
+    pub from_node: usize,
+    pub target_node: usize,
+}
+
+/// The route a moving piece takes, following the board's lines.
+#[derive(Clone, Copy, Debug)]
+pub enum MovePath {
+    /// Straight along a spoke, to or from the pūtahi.
+    Line { start: Vec2, end: Vec2 },
+    /// Around the outer circle between neighbouring kewai. Angles are about
+    /// the board centre; the radius blends in case the piece starts off the
+    /// circle (e.g. it was dropped near its destination).
+    Arc {
+        start_angle: f32,
+        end_angle: f32,
+        start_radius: f32,
+    },
+}
+
+impl MovePath {
+    /// The path from `start_pos` to `to_node` for a piece moving off `from_node`.
+    pub fn between(size: BoardSize, start_pos: Vec2, from_node: usize, to_node: usize) -> Self {
+        let end = size.node_position(to_node);
+        if from_node == size.center() || to_node == size.center() {
+            return MovePath::Line {
+                start: start_pos,
+                end,
+            };
+        }
+        let start_angle = start_pos.to_angle();
+        // Go the short way round.
+        let delta = (end.to_angle() - start_angle + PI).rem_euclid(TAU) - PI;
+        MovePath::Arc {
+            start_angle,
+            end_angle: start_angle + delta,
+            start_radius: start_pos.length(),
+        }
+    }
+
+    /// The position at `t` along the path, where 0 is the start and 1 the end.
+    /// `t` may stray outside `0..=1` for easing curves that overshoot.
+    pub fn sample(&self, t: f32) -> Vec2 {
+        match *self {
+            MovePath::Line { start, end } => start.lerp(end, t),
+            MovePath::Arc {
+                start_angle,
+                end_angle,
+                start_radius,
+            } => {
+                let angle = start_angle + (end_angle - start_angle) * t;
+                let radius = start_radius + (OUTER_RADIUS - start_radius) * t.min(1.0);
+                Vec2::from_angle(angle) * radius
+            }
+        }
+    }
+}
+
+/// A piece that was dropped somewhere illegal and is sliding back to its node.
+/// Unlike [`MovingPiece`], finishing this animation does not end the turn.
+#[derive(Component)]
+pub struct ReturningPiece {
     pub start_pos: Vec2,
     pub end_pos: Vec2,
     pub progress: f32,
-    pub target_node: usize,
 }
 
-const ANIMATION_SPEED: f32 = 5.0;
+/// A piece shaking side to side on its node, because it was clicked but can't move.
+#[derive(Component, Default)]
+pub struct ShakingPiece {
+    elapsed: f32,
+}
+
+const SHAKE_DURATION: f32 = 0.35;
+/// How far a shaking piece swings either side of its node.
+const SHAKE_DISTANCE: f32 = 6.0;
+const SHAKE_SWINGS: f32 = 3.0;
 
 fn handle_move_events(
     mut commands: Commands,
     mut move_events: MessageReader<MoveEvent>,
     mut pieces: Query<(&mut Piece, &Transform)>,
-    settings: Res<GameSettings>,
+    size: Res<BoardSize>,
+    animation: Res<AnimationSettings>,
+    mut game_state: ResMut<GameState>,
 ) {
     for event in move_events.read() {
         if let Ok((mut piece, transform)) = pieces.get_mut(event.piece_entity) {
             let start_pos = transform.translation.truncate();
-            let end_pos = node_position(event.target_node);
+            let end_pos = size.node_position(event.target_node);
+            let from_node = piece.node_index;
+            let path = MovePath::between(*size, start_pos, from_node, event.target_node);
 
+            game_state.start_move();
             piece.node_index = event.target_node;
 
-            if settings.instant_animation {
+            if animation.duration <= 0.0 {
                 commands
                     .entity(event.piece_entity)
                     .insert(Transform::from_xyz(end_pos.x, end_pos.y, 2.0));
                 commands.entity(event.piece_entity).insert(MovingPiece {
-                    start_pos: end_pos,
-                    end_pos,
+                    path,
                     progress: 1.0,
+                    from_node,
                     target_node: event.target_node,
                 });
             } else {
                 commands.entity(event.piece_entity).insert(MovingPiece {
-                    start_pos,
-                    end_pos,
+                    path,
                     progress: 0.0,
+                    from_node,
                     target_node: event.target_node,
                 });
             }
@@ -72,53 +160,88 @@ fn handle_move_events(
 fn animate_pieces(
     time: Res<Time>,
     mut pieces: Query<(&mut Transform, &mut MovingPiece)>,
-    settings: Res<GameSettings>,
+    animation: Res<AnimationSettings>,
+    reduced_motion: Res<ReducedMotion>,
 ) {
     for (mut transform, mut moving) in &mut pieces {
         if moving.progress < 1.0 {
-            let speed = if settings.instant_animation {
-                1000.0
-            } else {
-                ANIMATION_SPEED
-            };
-            moving.progress = (moving.progress + time.delta_secs() * speed).min(1.0);
-            let t = ease_out_quad(moving.progress);
-            let pos = moving.start_pos.lerp(moving.end_pos, t);
-            transform.translation = pos.extend(2.0);
+            let t = animation.advance(&mut moving.progress, time.delta_secs(), &reduced_motion);
+            transform.translation = moving.path.sample(t).extend(2.0);
+        }
+    }
+}
+
+fn animate_returning_pieces(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pieces: Query<(Entity, &mut Transform, &mut ReturningPiece)>,
+    animation: Res<AnimationSettings>,
+    reduced_motion: Res<ReducedMotion>,
+) {
+    for (entity, mut transform, mut returning) in &mut pieces {
+        let t = animation.advance(&mut returning.progress, time.delta_secs(), &reduced_motion);
+        let pos = returning.start_pos.lerp(returning.end_pos, t);
+        transform.translation = pos.extend(2.0);
+
+        if returning.progress >= 1.0 {
+            commands.entity(entity).remove::<ReturningPiece>();
         }
     }
 }
 
-fn ease_out_quad(t: f32) -> f32 {
-    1.0 - (1.0 - t) * (1.0 - t)
+fn shake_pieces(
+    mut commands: Commands,
+    time: Res<Time>,
+    size: Res<BoardSize>,
+    mut pieces: Query<(
+        Entity,
+        &Piece,
+        &mut Transform,
+        &mut ShakingPiece,
+        Has<MovingPiece>,
+    )>,
+) {
+    for (entity, piece, mut transform, mut shaking, moving) in &mut pieces {
+        // A move takes over the piece's position.
+        if moving {
+            commands.entity(entity).remove::<ShakingPiece>();
+            continue;
+        }
+        shaking.elapsed += time.delta_secs();
+        let t = (shaking.elapsed / SHAKE_DURATION).min(1.0);
+        let offset = (t * SHAKE_SWINGS * TAU).sin() * SHAKE_DISTANCE * (1.0 - t);
+        let node = size.node_position(piece.node_index);
+        transform.translation.x = node.x + offset;
+        transform.translation.y = node.y;
+        if t >= 1.0 {
+            commands.entity(entity).remove::<ShakingPiece>();
+        }
+    }
 }
 
 fn check_animation_complete(
     mut commands: Commands,
     pieces_moving: Query<(Entity, &Piece, &MovingPiece)>,
     pieces_all: Query<(&Piece, &Children)>,
-    mut game_state: ResMut<GameState>,
-    mut turn_events: MessageWriter<TurnChangeEvent>,
-    mut game_over_events: MessageWriter<GameOverEvent>,
+    rules: Rules,
+    settings: Res<GameSettings>,
+    mut completed: MessageWriter<MoveCompleted<MuTorere>>,
 ) {
     for (entity, piece, moving) in &pieces_moving {
         if moving.progress >= 1.0 {
             commands.entity(entity).remove::<MovingPiece>();
-
-            let next_turn = piece.color.opposite();
-
-            if !has_any_valid_moves(next_turn, &pieces_all) {
-                game_state.game_over = true;
-                game_state.winner = Some(piece.color);
-                game_over_events.write(GameOverEvent {
-                    winner: piece.color,
-                });
-            } else {
-                game_state.current_turn = next_turn;
-                turn_events.write(TurnChangeEvent {
-                    new_turn: next_turn,
-                });
-            }
+            // The move isn't in the history yet, but counts for what comes next.
+            let rules = rules.current().after_move();
+            let nodes = board_nodes(rules.size, pieces_all.iter().map(|(piece, _)| piece));
+            let blocked = settings
+                .rules
+                .blocked
+                .after_move(rules, &nodes, piece.color);
+            completed.write(MoveCompleted {
+                mv: (moving.from_node, moving.target_node),
+                blocked,
+                goal_reached: false,
+            });
         }
     }
 }
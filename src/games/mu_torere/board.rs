@@ -1,25 +1,252 @@
 //! Board representation and rendering for Mu Torere.
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use std::f32::consts::PI;
 
-use crate::screens::{ActiveGame, Screen};
+use crate::{
+    games::two_player::{
+        board::{blended, set_material_color, NODE_RADIUS, PIECE_RADIUS},
+        MoveRedone, MoveUndone,
+    },
+    screens::{gameplay::BoardBounds, Screen},
+    theme::{accessibility::Accessibility, motion::ReducedMotion, themes::Theme},
+};
 
 use super::{
     animation::MovingPiece,
+    input::{AllowedMoves, Hovered},
     is_playing_mu_torere,
-    state::{GameState, PieceColor},
+    state::{
+        choose_start_position, GameSettings, GameState, MuTorere, PieceColor, PutahiRule,
+        RestartGame, StartPosition,
+    },
+    style::{BoardAssets, PieceShadowVisual, PieceStyle},
+    GAME_ID,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Playing(ActiveGame::MuTorere)), spawn_board);
-    app.add_systems(Update, update_piece_colors.run_if(is_playing_mu_torere));
+    app.init_resource::<BoardSize>();
+    app.add_systems(
+        OnEnter(Screen::Playing(GAME_ID)),
+        (spawn_board.after(choose_start_position), set_board_bounds),
+    );
+    app.add_systems(
+        Update,
+        (
+            replay_history_moves,
+            update_piece_colors,
+            update_accessibility_shapes,
+            update_last_move_markers,
+        )
+            .run_if(is_playing_mu_torere),
+    );
+    app.add_systems(
+        Update,
+        (despawn_board, spawn_board)
+            .chain()
+            .after(choose_start_position)
+            .run_if(is_playing_mu_torere.and(on_message::<RestartGame>)),
+    );
 }
 
-pub const CENTER_INDEX: usize = 8;
 pub const OUTER_RADIUS: f32 = 200.0;
-pub const PIECE_RADIUS: f32 = 25.0;
-pub const LINE_WIDTH: f32 = 4.0;
+/// Space kept around the board when it is scaled to fit the window.
+const BOARD_MARGIN: f32 = 20.0;
+
+/// How many kewai the board has. Each side starts on half of them, and the
+/// pūtahi is numbered straight after them.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardSize {
+    pub kewai: usize,
+}
+
+impl Default for BoardSize {
+    fn default() -> Self {
+        Self { kewai: 8 }
+    }
+}
+
+impl BoardSize {
+    /// Every choice, in the order the game options menu steps through them.
+    pub const ALL: [BoardSize; 4] = [
+        BoardSize { kewai: 6 },
+        BoardSize { kewai: 8 },
+        BoardSize { kewai: 10 },
+        BoardSize { kewai: 12 },
+    ];
+
+    pub fn label(self) -> String {
+        format!("{} kewai", self.kewai)
+    }
+
+    /// The index of the pūtahi.
+    pub fn center(self) -> usize {
+        self.kewai
+    }
+
+    /// Kewai and the pūtahi.
+    pub fn node_count(self) -> usize {
+        self.kewai + 1
+    }
+
+    /// Where a node is drawn. Kewai go anticlockwise from the bottom of the board.
+    pub fn node_position(self, index: usize) -> Vec2 {
+        if index == self.center() {
+            Vec2::ZERO
+        } else {
+            let angle = (index as f32) * (2.0 * PI / self.kewai as f32) - PI / 2.0;
+            Vec2::new(angle.cos() * OUTER_RADIUS, angle.sin() * OUTER_RADIUS)
+        }
+    }
+
+    pub fn adjacencies(self, index: usize) -> Vec<usize> {
+        if index == self.center() {
+            (0..self.kewai).collect()
+        } else {
+            let prev = (index + self.kewai - 1) % self.kewai;
+            let next = (index + 1) % self.kewai;
+            vec![prev, next, self.center()]
+        }
+    }
+
+    /// The side whose piece starts on a kewai: White on the first half, Black
+    /// on the second.
+    pub fn starting_color(self, index: usize) -> PieceColor {
+        if index < self.kewai / 2 {
+            PieceColor::White
+        } else {
+            PieceColor::Black
+        }
+    }
+}
+
+/// What decides which moves are legal: the board's size and, for the pūtahi
+/// rule, which variant is played and how far into the game it is.
+#[derive(Clone, Copy, Debug)]
+pub struct MoveRules {
+    pub size: BoardSize,
+    pub putahi: PutahiRule,
+    pub moves_played: usize,
+}
+
+impl MoveRules {
+    /// The rules for the position after one more move.
+    pub fn after_move(self) -> Self {
+        Self {
+            moves_played: self.moves_played + 1,
+            ..self
+        }
+    }
+
+    /// Returns true if a piece may only move from `from` to `to` when it is
+    /// next to an opponent's piece.
+    fn needs_opponent(self, from: usize, to: usize) -> bool {
+        let center = self.size.center();
+        match self.putahi {
+            PutahiRule::EnterAndLeave => from == center || to == center,
+            PutahiRule::EnterOnly => to == center,
+            PutahiRule::Opening => {
+                self.moves_played < PutahiRule::OPENING_MOVES && (from == center || to == center)
+            }
+        }
+    }
+
+    /// Where the piece on `from` may move, given what stands on each node.
+    pub fn destinations(self, nodes: &[Option<PieceColor>], from: usize) -> Vec<usize> {
+        let Some(color) = nodes[from] else {
+            return Vec::new();
+        };
+        let adjacencies = self.size.adjacencies(from);
+        let next_to_opponent = adjacencies
+            .iter()
+            .any(|&node| nodes[node] == Some(color.opposite()));
+        adjacencies
+            .into_iter()
+            .filter(|&to| nodes[to].is_none())
+            .filter(|&to| next_to_opponent || !self.needs_opponent(from, to))
+            .collect()
+    }
+
+    /// Why the piece on `from` has nowhere to go, or `None` if it can move.
+    pub fn rejection(self, nodes: &[Option<PieceColor>], from: usize) -> Option<MoveRejection> {
+        if !self.destinations(nodes, from).is_empty() {
+            return None;
+        }
+        let has_empty_neighbour = self
+            .size
+            .adjacencies(from)
+            .into_iter()
+            .any(|node| nodes[node].is_none());
+        Some(if !has_empty_neighbour {
+            MoveRejection::NoEmptyNeighbour
+        } else if from == self.size.center() {
+            MoveRejection::CantLeavePutahi
+        } else {
+            // A kewai's other neighbours never need an opponent, so the empty
+            // one is the pūtahi.
+            MoveRejection::CantEnterPutahi
+        })
+    }
+
+    /// Returns true if `color` has a legal move, given what stands on each node.
+    pub fn can_move(self, nodes: &[Option<PieceColor>], color: PieceColor) -> bool {
+        (0..nodes.len())
+            .any(|from| nodes[from] == Some(color) && !self.destinations(nodes, from).is_empty())
+    }
+}
+
+/// Why the local player can't move a piece, to tell them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveRejection {
+    /// It's the other side's turn, on this device.
+    NotYourTurn,
+    /// It's the computer's turn.
+    ComputerThinking,
+    /// Every node next to the piece is taken.
+    NoEmptyNeighbour,
+    /// The pūtahi is empty, but the piece isn't next to an opponent's.
+    CantEnterPutahi,
+    /// The piece is on the pūtahi and isn't next to an opponent's.
+    CantLeavePutahi,
+    /// The move is legal but isn't one of the moves allowed right now, e.g. in
+    /// the tutorial.
+    NotAllowed,
+}
+
+impl MoveRejection {
+    pub fn text(self) -> &'static str {
+        match self {
+            MoveRejection::NotYourTurn => "Not your turn.",
+            MoveRejection::ComputerThinking => "Waiting for the computer.",
+            MoveRejection::NoEmptyNeighbour => "No empty adjacent node.",
+            MoveRejection::CantEnterPutahi => {
+                "May only enter the pūtahi when next to an opponent's piece."
+            }
+            MoveRejection::CantLeavePutahi => {
+                "May only leave the pūtahi when next to an opponent's piece."
+            }
+            MoveRejection::NotAllowed => "That isn't the move to play here.",
+        }
+    }
+}
+
+/// Reads the [`MoveRules`] for the position on the board.
+#[derive(SystemParam)]
+pub struct Rules<'w> {
+    size: Res<'w, BoardSize>,
+    settings: Res<'w, GameSettings>,
+    game_state: Res<'w, GameState>,
+}
+
+impl Rules<'_> {
+    pub fn current(&self) -> MoveRules {
+        MoveRules {
+            size: *self.size,
+            putahi: self.settings.rules.putahi,
+            moves_played: self.game_state.moves_played(),
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct BoardNode {
@@ -38,114 +265,162 @@ pub struct PieceVisual;
 #[derive(Component)]
 pub struct HighlightRing;
 
-fn get_node_position(index: usize) -> Vec2 {
-    if index == CENTER_INDEX {
-        Vec2::ZERO
-    } else {
-        let angle = (index as f32) * (2.0 * PI / 8.0) - PI / 2.0;
-        Vec2::new(angle.cos() * OUTER_RADIUS, angle.sin() * OUTER_RADIUS)
-    }
+/// A glyph drawn on a piece so its colour can be told without seeing it.
+#[derive(Component)]
+pub struct PieceGlyph;
+
+/// The shape-coded marker on a node showing it is a legal destination.
+#[derive(Component)]
+pub struct DestinationMarker;
+
+/// A faint piece on a node, showing that the hovered piece can move there.
+#[derive(Component)]
+pub struct MovePreview;
+
+/// The marker around one of the last move's nodes, shown until the next move.
+/// There are two: the first marks where the piece came from, the second where
+/// it went.
+#[derive(Component)]
+pub struct LastMoveMarker;
+
+/// An invisible, pickable area around a piece or node. It can grow beyond the
+/// visible sprite so the board is easier to hit with a finger.
+#[derive(Component)]
+pub struct TouchTarget {
+    pub base_size: f32,
 }
 
-pub fn get_adjacencies(index: usize) -> Vec<usize> {
-    if index == CENTER_INDEX {
-        (0..8).collect()
-    } else {
-        let prev = (index + 7) % 8;
-        let next = (index + 1) % 8;
-        vec![prev, next, CENTER_INDEX]
-    }
+/// The keyboard focus indicator. A single ring that sits over whichever piece
+/// or destination node currently has focus.
+#[derive(Component)]
+pub struct FocusRing;
+
+/// Z of the focus ring: above a piece's highlight ring, below the piece itself.
+pub const FOCUS_RING_Z: f32 = 1.95;
+
+fn set_board_bounds(mut bounds: ResMut<BoardBounds>) {
+    bounds.0 = Some(Vec2::splat(
+        2.0 * (OUTER_RADIUS + NODE_RADIUS + BOARD_MARGIN),
+    ));
 }
 
-fn spawn_board(mut commands: Commands) {
+/// The root of the board's lines, nodes and pieces.
+#[derive(Component)]
+struct Board;
+
+fn spawn_board(
+    mut commands: Commands,
+    size: Res<BoardSize>,
+    start: Res<StartPosition>,
+    board_assets: Res<BoardAssets>,
+    style: Res<PieceStyle>,
+    theme: Res<Theme>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Nodes and rings change colour one at a time, so each gets its own material.
+    let node_materials: Vec<_> = (0..size.node_count())
+        .map(|_| materials.add(blended(theme.board.node)))
+        .collect();
+    let pieces: Vec<_> = start
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(node, color)| {
+            color.map(|color| (node, color, materials.add(blended(Color::NONE))))
+        })
+        .collect();
+    let focus_material = materials.add(blended(theme.board.focus_ring));
+
     commands.spawn((
         Name::new("Board"),
+        Board,
         Transform::default(),
         Visibility::default(),
-        StateScoped(Screen::Playing(ActiveGame::MuTorere)),
-        children![board_lines(), board_nodes_and_pieces(),],
+        StateScoped(Screen::Playing(GAME_ID)),
+        children![
+            board_lines(*size, &board_assets),
+            board_nodes_and_pieces(
+                *size,
+                &board_assets,
+                &style,
+                node_materials,
+                pieces,
+                focus_material
+            ),
+        ],
     ));
 }
 
-fn board_lines() -> impl Bundle {
+fn despawn_board(mut commands: Commands, boards: Query<Entity, With<Board>>) {
+    for board in &boards {
+        commands.entity(board).despawn();
+    }
+}
+
+fn board_lines(size: BoardSize, board_assets: &BoardAssets) -> impl Bundle {
+    let spoke = board_assets.spoke.clone();
+    let material = board_assets.line_material.clone();
     (
         Name::new("Board Lines"),
         Transform::default(),
         Visibility::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
-            let line_color = Color::srgb(0.4, 0.4, 0.4);
-
-            for i in 0..8 {
-                let pos = get_node_position(i);
-                let next_pos = get_node_position((i + 1) % 8);
-
-                let mid = (pos + next_pos) / 2.0;
-                let diff = next_pos - pos;
-                let length = diff.length();
-                let angle = diff.y.atan2(diff.x);
-
-                parent.spawn((
-                    Name::new(format!("Outer Line {i}")),
-                    Sprite {
-                        color: line_color,
-                        custom_size: Some(Vec2::new(length, LINE_WIDTH)),
-                        ..default()
-                    },
-                    Transform::from_xyz(mid.x, mid.y, 0.0)
-                        .with_rotation(Quat::from_rotation_z(angle)),
-                ));
-
-                let center = Vec2::ZERO;
-                let mid_to_center = (pos + center) / 2.0;
-                let diff_to_center = center - pos;
-                let length_to_center = diff_to_center.length();
-                let angle_to_center = diff_to_center.y.atan2(diff_to_center.x);
-
-                parent.spawn((
-                    Name::new(format!("Radial Line {i}")),
-                    Sprite {
-                        color: line_color,
-                        custom_size: Some(Vec2::new(length_to_center, LINE_WIDTH)),
-                        ..default()
-                    },
-                    Transform::from_xyz(mid_to_center.x, mid_to_center.y, 0.0)
-                        .with_rotation(Quat::from_rotation_z(angle_to_center)),
-                ));
-            }
-        })),
+        Children::spawn((
+            Spawn((
+                Name::new("Outer Line"),
+                Mesh2d(board_assets.outer_edge.clone()),
+                MeshMaterial2d(material.clone()),
+                Transform::default(),
+            )),
+            SpawnWith(move |parent: &mut ChildSpawner| {
+                for i in 0..size.kewai {
+                    let pos = size.node_position(i);
+                    parent.spawn((
+                        Name::new(format!("Radial Line {i}")),
+                        Mesh2d(spoke.clone()),
+                        MeshMaterial2d(material.clone()),
+                        Transform::from_translation((pos / 2.0).extend(0.0))
+                            .with_rotation(Quat::from_rotation_z(pos.to_angle())),
+                    ));
+                }
+            }),
+        )),
     )
 }
 
-fn board_nodes_and_pieces() -> impl Bundle {
+fn board_nodes_and_pieces(
+    size: BoardSize,
+    board_assets: &BoardAssets,
+    style: &PieceStyle,
+    node_materials: Vec<Handle<ColorMaterial>>,
+    pieces: Vec<(usize, PieceColor, Handle<ColorMaterial>)>,
+    focus_material: Handle<ColorMaterial>,
+) -> impl Bundle {
+    let board_assets = board_assets.clone();
+    let style = style.clone();
     (
         Name::new("Nodes and Pieces"),
         Transform::default(),
         Visibility::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
-            for i in 0..9 {
-                let pos = get_node_position(i);
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for (i, material) in node_materials.into_iter().enumerate() {
+                let pos = size.node_position(i);
 
                 parent.spawn((
                     Name::new(format!("Node {i}")),
                     BoardNode { index: i },
-                    Sprite {
-                        color: Color::srgb(0.2, 0.2, 0.2),
-                        custom_size: Some(Vec2::splat(PIECE_RADIUS * 2.0 + 10.0)),
-                        ..default()
-                    },
+                    Mesh2d(board_assets.node.clone()),
+                    MeshMaterial2d(material),
                     Transform::from_xyz(pos.x, pos.y, 1.0),
+                    children![
+                        move_preview(&board_assets),
+                        destination_marker(&board_assets),
+                        touch_target(NODE_RADIUS * 2.0)
+                    ],
                 ));
             }
 
-            // Initial piece placement: White on nodes 0-3, Black on nodes 4-7
-            // Center (node 8) starts empty
-            for i in 0..8 {
-                let color = if i < 4 {
-                    PieceColor::White
-                } else {
-                    PieceColor::Black
-                };
-                let pos = get_node_position(i);
+            for (i, color, ring_material) in pieces {
+                let pos = size.node_position(i);
 
                 parent.spawn((
                     Name::new(format!("Piece {i}")),
@@ -155,175 +430,403 @@ fn board_nodes_and_pieces() -> impl Bundle {
                     },
                     Transform::from_xyz(pos.x, pos.y, 2.0),
                     Visibility::default(),
-                    children![highlight_ring(), piece_visual(color),],
+                    children![
+                        highlight_ring(&board_assets, ring_material),
+                        piece_visual(&board_assets, &style, color),
+                        touch_target(PIECE_RADIUS * 2.0),
+                    ],
                 ));
             }
+
+            parent.spawn(focus_ring(&board_assets, focus_material));
+            for _ in 0..2 {
+                parent.spawn(last_move_marker(&board_assets));
+            }
         })),
     )
 }
 
-fn highlight_ring() -> impl Bundle {
+fn highlight_ring(board_assets: &BoardAssets, material: Handle<ColorMaterial>) -> impl Bundle {
     (
         Name::new("Highlight Ring"),
         HighlightRing,
-        Sprite {
-            color: Color::srgba(1.0, 1.0, 0.0, 0.0),
-            custom_size: Some(Vec2::splat(PIECE_RADIUS * 2.0 + 16.0)),
-            ..default()
-        },
+        Mesh2d(board_assets.ring.clone()),
+        MeshMaterial2d(material),
         Transform::from_xyz(0.0, 0.0, -0.1),
     )
 }
 
-fn piece_visual(color: PieceColor) -> impl Bundle {
-    let visual_color = match color {
-        PieceColor::White => Color::srgb(0.95, 0.95, 0.95),
-        PieceColor::Black => Color::srgb(0.1, 0.1, 0.1),
+fn focus_ring(board_assets: &BoardAssets, material: Handle<ColorMaterial>) -> impl Bundle {
+    (
+        Name::new("Focus Ring"),
+        FocusRing,
+        Mesh2d(board_assets.ring.clone()),
+        MeshMaterial2d(material),
+        Transform::from_xyz(0.0, 0.0, FOCUS_RING_Z),
+        Visibility::Hidden,
+    )
+}
+
+fn move_preview(board_assets: &BoardAssets) -> impl Bundle {
+    (
+        Name::new("Move Preview"),
+        MovePreview,
+        Mesh2d(board_assets.piece.clone()),
+        MeshMaterial2d(board_assets.white_ghost.clone()),
+        // Below the destination marker, so the marker shows through it.
+        Transform::from_xyz(0.0, 0.0, 0.02),
+        Visibility::Hidden,
+    )
+}
+
+fn destination_marker(board_assets: &BoardAssets) -> impl Bundle {
+    (
+        Name::new("Destination Marker"),
+        DestinationMarker,
+        Mesh2d(board_assets.destination_marker.clone()),
+        MeshMaterial2d(board_assets.marker_material.clone()),
+        Transform::from_xyz(0.0, 0.0, 0.05),
+        Visibility::Hidden,
+    )
+}
+
+fn last_move_marker(board_assets: &BoardAssets) -> impl Bundle {
+    (
+        Name::new("Last Move Marker"),
+        LastMoveMarker,
+        Mesh2d(board_assets.last_move_frame.clone()),
+        MeshMaterial2d(board_assets.marker_material.clone()),
+        Transform::from_xyz(0.0, 0.0, 1.5),
+        Visibility::Hidden,
+    )
+}
+
+fn piece_visual(board_assets: &BoardAssets, style: &PieceStyle, color: PieceColor) -> impl Bundle {
+    let (shadow_offset, shadow_visibility) = match style.shadow {
+        Some(shadow) => (shadow.offset, Visibility::Inherited),
+        None => (Vec2::ZERO, Visibility::Hidden),
     };
 
     (
         Name::new("Piece Visual"),
         PieceVisual,
+        Transform::default(),
+        Visibility::default(),
+        children![
+            (
+                Name::new("Piece Shadow"),
+                PieceShadowVisual,
+                Mesh2d(board_assets.piece.clone()),
+                MeshMaterial2d(board_assets.shadow_material.clone()),
+                Transform::from_translation(shadow_offset.extend(-0.15)),
+                shadow_visibility,
+            ),
+            (
+                Name::new("Piece Fill"),
+                Mesh2d(board_assets.piece.clone()),
+                MeshMaterial2d(board_assets.fill(color)),
+                Transform::default(),
+            ),
+            (
+                Name::new("Piece Outline"),
+                Mesh2d(board_assets.piece_outline.clone()),
+                MeshMaterial2d(board_assets.outline_material.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.01),
+            ),
+            (
+                Name::new("Piece Glyph"),
+                PieceGlyph,
+                Mesh2d(board_assets.glyph(color)),
+                // The other side's colour always stands out against this one.
+                MeshMaterial2d(board_assets.fill(color.opposite())),
+                Transform::from_xyz(0.0, 0.0, 0.02),
+                Visibility::Hidden,
+            ),
+        ],
+    )
+}
+
+fn touch_target(base_size: f32) -> impl Bundle {
+    (
+        Name::new("Touch Target"),
+        TouchTarget { base_size },
         Sprite {
-            color: visual_color,
-            custom_size: Some(Vec2::splat(PIECE_RADIUS * 2.0)),
+            color: Color::NONE,
+            custom_size: Some(Vec2::splat(base_size)),
             ..default()
         },
-        Transform::default(),
+        // Picking events bubble up to the parent piece or node.
+        Pickable::default(),
+        Transform::from_xyz(0.0, 0.0, -0.05),
     )
 }
 
+/// How long one pulse of a movable piece's highlight ring takes, in seconds.
+const HIGHLIGHT_PULSE_PERIOD: f32 = 1.2;
+
 fn update_piece_colors(
+    time: Res<Time>,
+    reduced_motion: Res<ReducedMotion>,
+    theme: Res<Theme>,
+    rules: Rules,
+    allowed: Res<AllowedMoves>,
     game_state: Res<GameState>,
-    pieces: Query<(Entity, &Piece, &Children)>,
+    pieces: Query<(Entity, &Piece, &Children, Has<Hovered>)>,
     moving_pieces: Query<Entity, With<MovingPiece>>,
-    mut highlights: Query<&mut Sprite, With<HighlightRing>>,
+    highlights: Query<&MeshMaterial2d<ColorMaterial>, With<HighlightRing>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     let moving_entity = moving_pieces.iter().next();
-    let pieces_for_validation: Vec<_> = pieces.iter().map(|(_, p, c)| (p, c)).collect();
+    let rules = rules.current();
+    let nodes = board_nodes(rules.size, pieces.iter().map(|(_, piece, ..)| piece));
+    let highlight_alpha = if reduced_motion.0 {
+        0.8
+    } else {
+        let phase = time.elapsed_secs() * 2.0 * PI / HIGHLIGHT_PULSE_PERIOD;
+        0.65 + 0.15 * phase.sin()
+    };
 
-    for (entity, piece, children) in &pieces {
+    for (entity, piece, children, hovered) in &pieces {
         let should_highlight = if let Some(moving) = moving_entity {
             // While animating, only highlight the moving piece
             entity == moving
         } else {
             // Normal state: highlight pieces that can move
-            !game_state.game_over
-                && piece.color == game_state.current_turn
-                && can_piece_move_with_slice(piece, &pieces_for_validation)
+            !game_state.is_over()
+                && piece.color == game_state.turn()
+                && allowed.allows_from(piece.node_index)
+                && !rules.destinations(&nodes, piece.node_index).is_empty()
         };
 
         for child in children.iter() {
-            if let Ok(mut sprite) = highlights.get_mut(child) {
-                sprite.color = if should_highlight {
-                    Color::srgba(1.0, 1.0, 0.0, 0.8)
+            if let Ok(material) = highlights.get(child) {
+                let color = if should_highlight && hovered {
+                    theme.board.highlight_hovered
+                } else if should_highlight {
+                    theme.board.highlight.with_alpha(highlight_alpha)
                 } else {
-                    Color::srgba(1.0, 1.0, 0.0, 0.0)
+                    Color::NONE
                 };
+                set_material_color(&mut materials, &material.0, color);
             }
         }
     }
 }
 
-fn can_piece_move_with_slice(piece: &Piece, pieces: &[(&Piece, &Children)]) -> bool {
-    let adjacencies = get_adjacencies(piece.node_index);
+/// Shows or hides the glyphs, and swaps the highlight ring for a square, to
+/// match the accessibility settings.
+fn update_accessibility_shapes(
+    accessibility: Res<Accessibility>,
+    board_assets: Res<BoardAssets>,
+    mut rings: Query<&mut Mesh2d, With<HighlightRing>>,
+    mut glyphs: Query<&mut Visibility, With<PieceGlyph>>,
+) {
+    let ring_mesh = if accessibility.shape_highlights {
+        &board_assets.source_frame
+    } else {
+        &board_assets.ring
+    };
+    for mut mesh in &mut rings {
+        mesh.set_if_neq(Mesh2d(ring_mesh.clone()));
+    }
 
-    for adj_index in adjacencies {
-        if is_node_empty_slice(adj_index, pieces) {
-            if adj_index == CENTER_INDEX || piece.node_index == CENTER_INDEX {
-                if is_adjacent_to_opponent_slice(piece, pieces) {
-                    return true;
+    let glyph_visibility = if accessibility.piece_glyphs {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in &mut glyphs {
+        visibility.set_if_neq(glyph_visibility);
+    }
+}
+
+/// Marks where the last move went from and to, whoever made it, so a
+/// computer's move can be followed after it lands.
+fn update_last_move_markers(
+    size: Res<BoardSize>,
+    game_state: Res<GameState>,
+    mut last_move_markers: Query<(&mut Transform, &mut Visibility), With<LastMoveMarker>>,
+) {
+    let last_move_nodes = game_state.last_move().map(|(from, to)| [from, to]);
+    for (i, (mut transform, mut visibility)) in last_move_markers.iter_mut().enumerate() {
+        match last_move_nodes.and_then(|nodes| nodes.get(i).copied()) {
+            Some(node) => {
+                let position = size.node_position(node).extend(transform.translation.z);
+                if transform.translation != position {
+                    transform.translation = position;
                 }
-            } else {
-                return true;
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
             }
         }
     }
-    false
 }
 
-fn is_node_empty_slice(node_index: usize, pieces: &[(&Piece, &Children)]) -> bool {
-    for (piece, _) in pieces {
-        if piece.node_index == node_index {
-            return false;
-        }
-    }
-    true
+/// Where `piece` may move, given where every piece stands. The rules
+/// themselves are in [`MoveRules::destinations`].
+pub fn get_valid_moves(
+    rules: MoveRules,
+    piece: &Piece,
+    pieces: &Query<(&Piece, &Children)>,
+) -> Vec<usize> {
+    let nodes = board_nodes(rules.size, pieces.iter().map(|(piece, _)| piece));
+    rules.destinations(&nodes, piece.node_index)
 }
 
-fn is_adjacent_to_opponent_slice(piece: &Piece, pieces: &[(&Piece, &Children)]) -> bool {
-    let adjacencies = get_adjacencies(piece.node_index);
-    for (other_piece, _) in pieces {
-        if other_piece.color != piece.color && adjacencies.contains(&other_piece.node_index) {
-            return true;
-        }
+/// What stands on each node, with `to_move` to move.
+pub fn board_position<'a>(
+    size: BoardSize,
+    to_move: PieceColor,
+    pieces: impl IntoIterator<Item = &'a Piece>,
+) -> StartPosition {
+    StartPosition {
+        nodes: board_nodes(size, pieces),
+        to_move,
     }
-    false
 }
 
-pub fn can_piece_move(piece: &Piece, pieces: &Query<(&Piece, &Children)>) -> bool {
-    let adjacencies = get_adjacencies(piece.node_index);
-
-    for adj_index in adjacencies {
-        if is_node_empty(adj_index, pieces) {
-            if adj_index == CENTER_INDEX || piece.node_index == CENTER_INDEX {
-                if is_adjacent_to_opponent(piece, pieces) {
-                    return true;
-                }
-            } else {
-                return true;
-            }
-        }
+/// What stands on each node, as [`MoveRules`] reads it.
+pub fn board_nodes<'a>(
+    size: BoardSize,
+    pieces: impl IntoIterator<Item = &'a Piece>,
+) -> Vec<Option<PieceColor>> {
+    let mut nodes = vec![None; size.node_count()];
+    for piece in pieces {
+        nodes[piece.node_index] = Some(piece.color);
     }
-    false
+    nodes
 }
 
-pub fn is_node_empty(node_index: usize, pieces: &Query<(&Piece, &Children)>) -> bool {
-    for (piece, _) in pieces.iter() {
-        if piece.node_index == node_index {
-            return false;
+/// Puts pieces straight back, or forward, for moves that were undone or redone.
+fn replay_history_moves(
+    mut undone: MessageReader<MoveUndone<MuTorere>>,
+    mut redone: MessageReader<MoveRedone<MuTorere>>,
+    size: Res<BoardSize>,
+    mut pieces: Query<(&mut Piece, &mut Transform)>,
+) {
+    let undone = undone.read().map(|event| (event.mv.1, event.mv.0));
+    let redone = redone.read().map(|event| event.mv);
+    for (from, to) in undone.chain(redone) {
+        if let Some((mut piece, mut transform)) = pieces
+            .iter_mut()
+            .find(|(piece, _)| piece.node_index == from)
+        {
+            piece.node_index = to;
+            transform.translation = size.node_position(to).extend(transform.translation.z);
         }
     }
-    true
 }
 
-pub fn is_adjacent_to_opponent(piece: &Piece, pieces: &Query<(&Piece, &Children)>) -> bool {
-    let adjacencies = get_adjacencies(piece.node_index);
-    for (other_piece, _) in pieces.iter() {
-        if other_piece.color != piece.color && adjacencies.contains(&other_piece.node_index) {
-            return true;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{mu_torere::state::BlockedRule, two_player::Blocked};
+
+    fn rules(putahi: PutahiRule, moves_played: usize) -> MoveRules {
+        MoveRules {
+            size: BoardSize::default(),
+            putahi,
+            moves_played,
         }
     }
-    false
-}
 
-pub fn get_valid_moves(piece: &Piece, pieces: &Query<(&Piece, &Children)>) -> Vec<usize> {
-    let mut moves = Vec::new();
-    let adjacencies = get_adjacencies(piece.node_index);
+    fn nodes(text: &str) -> Vec<Option<PieceColor>> {
+        StartPosition::from_text(text, PieceColor::White).nodes
+    }
 
-    for adj_index in adjacencies {
-        if is_node_empty(adj_index, pieces) {
-            if adj_index == CENTER_INDEX || piece.node_index == CENTER_INDEX {
-                if is_adjacent_to_opponent(piece, pieces) {
-                    moves.push(adj_index);
-                }
-            } else {
-                moves.push(adj_index);
-            }
+    /// White on every other kewai and the pūtahi, with no Black piece for
+    /// them to be next to.
+    const LONE_WHITE: &str = "W.W.W.W.W";
+    /// As [`LONE_WHITE`], with the pūtahi empty.
+    const LONE_WHITE_OFF_PUTAHI: &str = "W.W.W.W..";
+
+    #[test]
+    fn pieces_next_to_an_opponent_enter_the_putahi() {
+        let start = nodes("WWWWBBBB.");
+        for putahi in PutahiRule::ALL {
+            assert_eq!(rules(putahi, 0).destinations(&start, 0), vec![8]);
+            assert_eq!(
+                rules(putahi, 0).destinations(&start, 1),
+                Vec::<usize>::new()
+            );
         }
     }
-    moves
-}
 
-pub fn has_any_valid_moves(color: PieceColor, pieces: &Query<(&Piece, &Children)>) -> bool {
-    for (piece, _) in pieces.iter() {
-        if piece.color == color && can_piece_move(piece, pieces) {
-            return true;
-        }
+    #[test]
+    fn enter_and_leave_restricts_both_ways() {
+        let rules = rules(PutahiRule::EnterAndLeave, 10);
+        assert!(rules.destinations(&nodes(LONE_WHITE), 8).is_empty());
+        assert_eq!(
+            rules.destinations(&nodes(LONE_WHITE_OFF_PUTAHI), 0),
+            vec![7, 1]
+        );
+    }
+
+    #[test]
+    fn enter_only_lets_pieces_leave_freely() {
+        let rules = rules(PutahiRule::EnterOnly, 0);
+        assert_eq!(rules.destinations(&nodes(LONE_WHITE), 8), vec![1, 3, 5, 7]);
+        assert_eq!(
+            rules.destinations(&nodes(LONE_WHITE_OFF_PUTAHI), 0),
+            vec![7, 1]
+        );
+    }
+
+    #[test]
+    fn opening_rule_lapses_after_the_opening() {
+        let during = rules(PutahiRule::Opening, PutahiRule::OPENING_MOVES - 1);
+        assert!(during.destinations(&nodes(LONE_WHITE), 8).is_empty());
+        assert_eq!(
+            during.destinations(&nodes(LONE_WHITE_OFF_PUTAHI), 0),
+            vec![7, 1]
+        );
+
+        let after = rules(PutahiRule::Opening, PutahiRule::OPENING_MOVES);
+        assert_eq!(after.destinations(&nodes(LONE_WHITE), 8), vec![1, 3, 5, 7]);
+        assert_eq!(
+            after.destinations(&nodes(LONE_WHITE_OFF_PUTAHI), 0),
+            vec![7, 1, 8]
+        );
+    }
+
+    #[test]
+    fn empty_nodes_have_no_destinations() {
+        let start = nodes("WWWWBBBB.");
+        assert!(rules(PutahiRule::EnterOnly, 0)
+            .destinations(&start, 8)
+            .is_empty());
     }
-    false
-}
 
-pub fn node_position(index: usize) -> Vec2 {
-    get_node_position(index)
+    #[test]
+    fn blocked_rule_decides_what_a_blocked_side_does() {
+        let rules = rules(PutahiRule::EnterAndLeave, 0);
+        let open = nodes("WWWWBBBB.");
+        let black_blocked = nodes("WBBBBWW.W");
+        let both_blocked = nodes("WWWWWWWW.");
+
+        for blocked in BlockedRule::ALL {
+            assert_eq!(
+                blocked.after_move(rules, &open, PieceColor::White),
+                Blocked::No
+            );
+        }
+        assert_eq!(
+            BlockedRule::Loses.after_move(rules, &black_blocked, PieceColor::White),
+            Blocked::OpponentLoses
+        );
+        assert_eq!(
+            BlockedRule::Passes.after_move(rules, &black_blocked, PieceColor::White),
+            Blocked::OpponentPasses
+        );
+        assert_eq!(
+            BlockedRule::Loses.after_move(rules, &both_blocked, PieceColor::White),
+            Blocked::OpponentLoses
+        );
+        assert_eq!(
+            BlockedRule::Passes.after_move(rules, &both_blocked, PieceColor::White),
+            Blocked::Both
+        );
+    }
 }
@@ -3,91 +3,66 @@
 use bevy::prelude::*;
 use rand::prelude::*;
 
+use crate::games::two_player::ComputerTurn;
+
 use super::{
     animation::MoveEvent,
-    board::{get_valid_moves, Piece},
+    board::{board_position, get_valid_moves, BoardSize, Piece, Rules},
     is_playing_mu_torere,
-    state::{GameMode, GameSettings, GameState, PieceColor},
+    puzzle::ActivePuzzle,
+    state::MuTorere,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<ComputerThinkTimer>();
     app.add_systems(Update, computer_turn.run_if(is_playing_mu_torere));
 }
 
-#[derive(Resource)]
-pub struct ComputerThinkTimer {
-    timer: Timer,
-    thinking: bool,
-}
-
-impl Default for ComputerThinkTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(0.5, TimerMode::Once),
-            thinking: false,
-        }
-    }
-}
-
+/// Plays a random legal move when the framework says it's the computer's turn,
+/// or in a puzzle, the best defence.
 fn computer_turn(
-    time: Res<Time>,
-    mut think_timer: ResMut<ComputerThinkTimer>,
-    settings: Res<GameSettings>,
-    game_state: Res<GameState>,
+    mut turns: MessageReader<ComputerTurn<MuTorere>>,
+    rules: Rules,
+    size: Res<BoardSize>,
+    puzzle: Option<Res<ActivePuzzle>>,
     pieces: Query<(Entity, &Piece, &Children)>,
     pieces_for_validation: Query<(&Piece, &Children)>,
-    moving_pieces: Query<&super::animation::MovingPiece>,
     mut move_events: MessageWriter<MoveEvent>,
 ) {
-    if settings.mode != GameMode::VsComputer {
-        return;
-    }
-
-    if game_state.game_over {
-        think_timer.thinking = false;
-        return;
-    }
-
-    if game_state.current_turn != PieceColor::Black {
-        think_timer.thinking = false;
-        return;
-    }
-
-    if !moving_pieces.is_empty() {
-        think_timer.thinking = false;
-        return;
-    }
-
-    if !think_timer.thinking {
-        think_timer.thinking = true;
-        think_timer.timer.reset();
-        return;
-    }
-
-    think_timer.timer.tick(time.delta());
-
-    if !think_timer.timer.is_finished() {
-        return;
-    }
+    for turn in turns.read() {
+        if let Some(puzzle) = &puzzle {
+            let position =
+                board_position(*size, turn.side, pieces.iter().map(|(_, piece, _)| piece));
+            let piece = puzzle.best_move(&position).and_then(|(from, to)| {
+                pieces
+                    .iter()
+                    .find(|(_, piece, _)| piece.node_index == from)
+                    .map(|(entity, _, _)| (entity, to))
+            });
+            if let Some((piece_entity, target_node)) = piece {
+                move_events.write(MoveEvent {
+                    piece_entity,
+                    target_node,
+                });
+            }
+            continue;
+        }
 
-    let mut valid_moves: Vec<(Entity, usize)> = Vec::new();
+        let mut valid_moves: Vec<(Entity, usize)> = Vec::new();
 
-    for (entity, piece, _) in &pieces {
-        if piece.color == PieceColor::Black {
-            let moves = get_valid_moves(piece, &pieces_for_validation);
-            for target in moves {
-                valid_moves.push((entity, target));
+        for (entity, piece, _) in &pieces {
+            if piece.color == turn.side {
+                let moves = get_valid_moves(rules.current(), piece, &pieces_for_validation);
+                for target in moves {
+                    valid_moves.push((entity, target));
+                }
             }
         }
-    }
 
-    if let Some((entity, target)) = valid_moves.choose(&mut rand::rng()) {
-        move_events.write(MoveEvent {
-            piece_entity: *entity,
-            target_node: *target,
-        });
+        if let Some((entity, target)) = valid_moves.choose(&mut rand::rng()) {
+            move_events.write(MoveEvent {
+                piece_entity: *entity,
+                target_node: *target,
+            });
+        }
     }
-
-    think_timer.thinking = false;
 }
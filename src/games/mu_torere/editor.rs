@@ -0,0 +1,380 @@
+//! The start position editor, for setting up custom and teaching positions.
+//!
+//! Pieces are dragged from the palette onto nodes, or between nodes, and
+//! clicking a node cycles it through empty, White and Black, so the editor
+//! works from a keyboard or gamepad too. The game starts once the position
+//! is valid: each side has half the kewai, and the side to move can move.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    gamepad::gamepad_just_pressed,
+    games::{
+        registry::OptionAction,
+        two_player::{self, TwoPlayerGame},
+    },
+    input_map::{action_just_pressed, InputAction},
+    menus::{start_game, Menu},
+    theme::{accessibility::AccessibleName, prelude::*, themes::Theme},
+};
+
+use super::{
+    accessibility::node_name,
+    board::{BoardSize, MoveRules},
+    is_mu_torere_selected,
+    state::{BlockedRule, CustomStart, GameSettings, MuTorere, PieceColor, StartPosition},
+    GAME_ID,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<EditorPosition>();
+    app.add_systems(
+        OnEnter(Menu::StartEditor),
+        (reset_editor, spawn_editor)
+            .chain()
+            .run_if(is_mu_torere_selected),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_node_colors,
+            update_to_move_label,
+            update_status_label,
+            go_back.run_if(
+                action_just_pressed(InputAction::Cancel)
+                    .or(gamepad_just_pressed(GamepadButton::East)),
+            ),
+        )
+            .run_if(in_state(Menu::StartEditor).and(is_mu_torere_selected)),
+    );
+}
+
+/// Distance from the centre of the editor's board to each kewai, in pixels.
+const BOARD_RADIUS: f32 = 140.0;
+const NODE_SIZE: f32 = 52.0;
+
+/// The position being set up.
+#[derive(Resource, Default)]
+struct EditorPosition(StartPosition);
+
+/// A node on the editor's board.
+#[derive(Component)]
+struct EditorNode(usize);
+
+/// A piece in the palette, to drag onto the board.
+#[derive(Component)]
+struct PalettePiece(PieceColor);
+
+#[derive(Component)]
+struct ToMoveLabel;
+
+#[derive(Component)]
+struct StatusLabel;
+
+fn reset_editor(mut position: ResMut<EditorPosition>, size: Res<BoardSize>) {
+    position.0 = StartPosition::standard(*size);
+}
+
+fn spawn_editor(mut commands: Commands, size: Res<BoardSize>) {
+    let size = *size;
+    commands.spawn((
+        widget::ui_root("Start Editor"),
+        GlobalZIndex(2),
+        StateScoped(Menu::StartEditor),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(widget::header("Custom Start"));
+            parent.spawn((
+                Name::new("Editor Row"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(40.0),
+                    ..default()
+                },
+                children![board(size), palette()],
+            ));
+            parent.spawn((
+                Name::new("Side to Move"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    widget::label("To move"),
+                    widget::button_small("<", switch_side_to_move),
+                    (
+                        Node {
+                            justify_content: JustifyContent::Center,
+                            min_width: Val::Px(120.0),
+                            ..default()
+                        },
+                        children![(widget::label(""), ToMoveLabel)],
+                    ),
+                    widget::button_small(">", switch_side_to_move),
+                ],
+            ));
+            parent.spawn((widget::label(""), StatusLabel));
+            parent.spawn((
+                Name::new("Editor Buttons"),
+                Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                    parent.spawn(widget::button_medium("Standard", set_standard));
+                    parent.spawn(widget::button_medium("Clear", clear_board));
+                    for option in two_player::mode_options() {
+                        if let OptionAction::Start(apply) = option.action {
+                            parent.spawn(widget::button_medium(
+                                format!("Start {}", option.label),
+                                start(apply),
+                            ));
+                        }
+                    }
+                    parent.spawn(widget::button_medium("Back", go_back_on_click));
+                })),
+            ));
+        })),
+    ));
+}
+
+fn board(size: BoardSize) -> impl Bundle {
+    (
+        Name::new("Editor Board"),
+        Node {
+            width: Val::Px(2.0 * BOARD_RADIUS + NODE_SIZE),
+            height: Val::Px(2.0 * BOARD_RADIUS + NODE_SIZE),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for index in 0..size.node_count() {
+                // UI y points down, board y up.
+                let position = size.node_position(index) / super::board::OUTER_RADIUS
+                    * BOARD_RADIUS
+                    * Vec2::new(1.0, -1.0)
+                    + Vec2::splat(BOARD_RADIUS);
+                parent
+                    .spawn((
+                        Name::new(format!("Editor Node {index}")),
+                        EditorNode(index),
+                        Button,
+                        AccessibleName(node_name(size, index)),
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(position.x),
+                            top: Val::Px(position.y),
+                            width: Val::Px(NODE_SIZE),
+                            height: Val::Px(NODE_SIZE),
+                            border: UiRect::all(Val::Px(3.0)),
+                            ..default()
+                        },
+                        BorderRadius::MAX,
+                        BackgroundColor::default(),
+                        BorderColor::default(),
+                    ))
+                    .observe(cycle_node)
+                    .observe(drop_on_node);
+            }
+        })),
+    )
+}
+
+fn palette() -> impl Bundle {
+    (
+        Name::new("Palette"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        children![
+            widget::label("Drag onto the board"),
+            palette_piece(PieceColor::White),
+            palette_piece(PieceColor::Black),
+        ],
+    )
+}
+
+fn palette_piece(color: PieceColor) -> impl Bundle {
+    (
+        Name::new(format!("{} Palette Piece", MuTorere::side_name(color))),
+        PalettePiece(color),
+        Node {
+            width: Val::Px(NODE_SIZE),
+            height: Val::Px(NODE_SIZE),
+            border: UiRect::all(Val::Px(3.0)),
+            ..default()
+        },
+        BorderRadius::MAX,
+        BackgroundColor::default(),
+        BorderColor::default(),
+    )
+}
+
+/// Clicking a node cycles it through empty, White and Black.
+fn cycle_node(
+    click: On<Pointer<Click>>,
+    nodes: Query<&EditorNode>,
+    mut position: ResMut<EditorPosition>,
+) {
+    let Ok(node) = nodes.get(click.entity) else {
+        return;
+    };
+    let contents = &mut position.0.nodes[node.0];
+    *contents = match *contents {
+        None => Some(PieceColor::White),
+        Some(PieceColor::White) => Some(PieceColor::Black),
+        Some(PieceColor::Black) => None,
+    };
+}
+
+/// Dropping a palette piece on a node puts a piece there. Dropping a node
+/// moves its piece, swapping with whatever was there.
+fn drop_on_node(
+    drop: On<Pointer<DragDrop>>,
+    nodes: Query<&EditorNode>,
+    palette: Query<&PalettePiece>,
+    mut position: ResMut<EditorPosition>,
+) {
+    let Ok(target) = nodes.get(drop.entity) else {
+        return;
+    };
+    if let Ok(piece) = palette.get(drop.dropped) {
+        position.0.nodes[target.0] = Some(piece.0);
+    } else if let Ok(source) = nodes.get(drop.dropped) {
+        position.0.nodes.swap(source.0, target.0);
+    }
+}
+
+fn switch_side_to_move(_: On<Pointer<Click>>, mut position: ResMut<EditorPosition>) {
+    position.0.to_move = position.0.to_move.opposite();
+}
+
+fn set_standard(
+    _: On<Pointer<Click>>,
+    mut position: ResMut<EditorPosition>,
+    mut custom: ResMut<CustomStart>,
+    size: Res<BoardSize>,
+) {
+    position.0 = StartPosition::standard(*size);
+    custom.0 = None;
+}
+
+fn clear_board(_: On<Pointer<Click>>, mut position: ResMut<EditorPosition>) {
+    position.0.nodes.fill(None);
+}
+
+/// Why the position can't be played from, if it can't.
+fn problem(position: &StartPosition, size: BoardSize, settings: &GameSettings) -> Option<String> {
+    let rules = opening_rules(size, settings);
+    let per_side = rules.size.kewai / 2;
+    for color in MuTorere::SIDES {
+        let count = position
+            .nodes
+            .iter()
+            .filter(|&&node| node == Some(color))
+            .count();
+        if count != per_side {
+            return Some(format!(
+                "{} needs {per_side} pieces, not {count}.",
+                MuTorere::side_name(color)
+            ));
+        }
+    }
+    let to_move = position.to_move;
+    match settings.rules.blocked {
+        BlockedRule::Loses if !rules.can_move(&position.nodes, to_move) => Some(format!(
+            "{} has no legal move.",
+            MuTorere::side_name(to_move)
+        )),
+        // A blocked side passes, so the game only can't start if both are blocked.
+        BlockedRule::Passes
+            if !rules.can_move(&position.nodes, to_move)
+                && !rules.can_move(&position.nodes, to_move.opposite()) =>
+        {
+            Some("Neither side has a legal move.".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The rules the position will be played under, from its first move.
+fn opening_rules(size: BoardSize, settings: &GameSettings) -> MoveRules {
+    MoveRules {
+        size,
+        putahi: settings.rules.putahi,
+        moves_played: 0,
+    }
+}
+
+fn start(
+    apply: fn(&mut World),
+) -> impl Fn(On<Pointer<Click>>, Commands, Res<EditorPosition>, Res<BoardSize>, Res<GameSettings>) {
+    move |_, mut commands, position, size, settings| {
+        if problem(&position.0, *size, &settings).is_some() {
+            return;
+        }
+        let position = position.0.clone();
+        commands.queue(move |world: &mut World| {
+            world.resource_mut::<CustomStart>().0 = Some(position);
+            start_game(world, GAME_ID, apply);
+        });
+    }
+}
+
+fn update_node_colors(
+    theme: Res<Theme>,
+    position: Res<EditorPosition>,
+    mut nodes: Query<(&EditorNode, &mut BackgroundColor, &mut BorderColor)>,
+    mut palette: Query<
+        (&PalettePiece, &mut BackgroundColor, &mut BorderColor),
+        Without<EditorNode>,
+    >,
+) {
+    let fill = |color: Option<PieceColor>| match color {
+        None => theme.board.node,
+        Some(PieceColor::White) => theme.board.white_piece,
+        Some(PieceColor::Black) => theme.board.black_piece,
+    };
+    let border = BorderColor::all(theme.board.line);
+    for (node, mut background, mut border_color) in &mut nodes {
+        background.set_if_neq(BackgroundColor(fill(position.0.nodes[node.0])));
+        border_color.set_if_neq(border);
+    }
+    for (piece, mut background, mut border_color) in &mut palette {
+        background.set_if_neq(BackgroundColor(fill(Some(piece.0))));
+        border_color.set_if_neq(border);
+    }
+}
+
+fn update_to_move_label(
+    position: Res<EditorPosition>,
+    mut label: Single<&mut Text, With<ToMoveLabel>>,
+) {
+    let text = MuTorere::side_name(position.0.to_move);
+    if label.0 != text {
+        label.0 = text.to_string();
+    }
+}
+
+fn update_status_label(
+    position: Res<EditorPosition>,
+    size: Res<BoardSize>,
+    settings: Res<GameSettings>,
+    mut label: Single<&mut Text, With<StatusLabel>>,
+) {
+    let text =
+        problem(&position.0, *size, &settings).unwrap_or_else(|| "Ready to start.".to_string());
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, next_menu: ResMut<NextState<Menu>>) {
+    go_back(next_menu);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::GameOptions);
+}
@@ -0,0 +1,186 @@
+//! Tells the player why the piece they picked can't move: the piece shakes,
+//! a sound plays, and the reason shows for a moment above the footer and is
+//! read out. Asked for a hint, suggests a move the same way and focuses it.
+
+use bevy::prelude::*;
+
+use crate::{
+    input_map::{action_just_pressed, InputAction},
+    screens::{gameplay::HUD_FOOTER_HEIGHT, Screen},
+    theme::{accessibility::Announce, motion::ReducedMotion, widget},
+    Pause,
+};
+
+use super::{
+    accessibility::node_name,
+    animation::{MovingPiece, ReturningPiece, ShakingPiece},
+    board::{board_position, MoveRejection, MoveRules, Piece, Rules},
+    input::{movable_destinations, AllowedMoves, PieceFocus},
+    is_playing_mu_torere,
+    puzzle::ActivePuzzle,
+    solver::{moves, play},
+    sound::PlaySound,
+    state::{GameState, StartPosition},
+    GAME_ID,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<MoveRejected>();
+    app.add_systems(
+        Update,
+        (
+            suggest_move.run_if(action_just_pressed(InputAction::Hint)),
+            show_rejection,
+            expire_hints,
+        )
+            .chain()
+            .run_if(is_playing_mu_torere),
+    );
+}
+
+/// The local player tried to move a piece that can't move, or to move when
+/// they can't.
+#[derive(Message)]
+pub struct MoveRejected {
+    /// The piece to shake, if one was picked.
+    pub piece: Option<Entity>,
+    pub reason: MoveRejection,
+}
+
+/// How long a hint stays up, in seconds.
+const HINT_DURATION: f32 = 2.5;
+
+#[derive(Component)]
+struct Hint(Timer);
+
+fn show_rejection(
+    mut commands: Commands,
+    mut rejections: MessageReader<MoveRejected>,
+    hints: Query<Entity, With<Hint>>,
+    reduced_motion: Res<ReducedMotion>,
+    mut announce: MessageWriter<Announce>,
+    mut sounds: MessageWriter<PlaySound>,
+) {
+    let Some(rejected) = rejections.read().last() else {
+        return;
+    };
+    sounds.write(PlaySound::Illegal);
+    if let Some(piece) = rejected.piece
+        && !reduced_motion.0
+    {
+        commands.entity(piece).try_insert(ShakingPiece::default());
+    }
+    show_hint(&mut commands, &hints, rejected.reason.text());
+    announce.write(Announce(rejected.reason.text().to_string()));
+}
+
+/// Focuses a move for the local player: in a puzzle the solution's, otherwise
+/// the best-looking one a move ahead.
+fn suggest_move(
+    mut commands: Commands,
+    rules: Rules,
+    game_state: Res<GameState>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    puzzle: Option<Res<ActivePuzzle>>,
+    pieces: Query<(Entity, &Piece)>,
+    pieces_for_validation: Query<(&Piece, &Children)>,
+    hints: Query<Entity, With<Hint>>,
+    mut focus: ResMut<PieceFocus>,
+    mut announce: MessageWriter<Announce>,
+) {
+    let rules = rules.current();
+    let candidates: Vec<(Entity, (usize, usize))> = pieces
+        .iter()
+        .flat_map(|(entity, piece)| {
+            movable_destinations(
+                rules,
+                piece,
+                &game_state,
+                &pause,
+                &moving_pieces,
+                &allowed,
+                &pieces_for_validation,
+            )
+            .into_iter()
+            .map(move |to| (entity, (piece.node_index, to)))
+        })
+        .collect();
+    let position = board_position(
+        rules.size,
+        game_state.turn(),
+        pieces.iter().map(|(_, piece)| piece),
+    );
+    let solution = puzzle.and_then(|puzzle| puzzle.best_move(&position));
+    let suggestion = candidates
+        .iter()
+        .find(|&&(_, mv)| Some(mv) == solution)
+        .or_else(|| {
+            candidates
+                .iter()
+                .max_by_key(|&&(_, mv)| move_value(rules, &position, mv))
+        });
+    let Some(&(piece, (from, to))) = suggestion else {
+        return;
+    };
+    focus.piece = Some(piece);
+    focus.destination = Some(to);
+    let text = format!(
+        "Hint: move from {} to {}.",
+        node_name(rules.size, from),
+        node_name(rules.size, to)
+    );
+    show_hint(&mut commands, &hints, &text);
+    announce.write(Announce(text));
+}
+
+/// How good `mv` looks a move ahead: 2 if it leaves the other side without a
+/// move, 0 if their reply could leave the mover without one, otherwise 1.
+fn move_value(rules: MoveRules, position: &StartPosition, mv: (usize, usize)) -> usize {
+    let after = play(position, mv);
+    let rules = rules.after_move();
+    if !rules.can_move(&after.nodes, after.to_move) {
+        return 2;
+    }
+    let blocked_by_reply = moves(rules, &after).into_iter().any(|reply| {
+        let reply = play(&after, reply);
+        !rules.after_move().can_move(&reply.nodes, reply.to_move)
+    });
+    if blocked_by_reply {
+        0
+    } else {
+        1
+    }
+}
+
+/// Shows `text` above the footer for a moment.
+fn show_hint(commands: &mut Commands, hints: &Query<Entity, With<Hint>>, text: &str) {
+    // A new hint replaces the old one rather than stacking up.
+    for hint in hints {
+        commands.entity(hint).despawn();
+    }
+    commands.spawn((
+        Name::new("Hint"),
+        Hint(Timer::from_seconds(HINT_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(HUD_FOOTER_HEIGHT),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(2),
+        Pickable::IGNORE,
+        StateScoped(Screen::Playing(GAME_ID)),
+        children![widget::label(text.to_string())],
+    ));
+}
+
+fn expire_hints(mut commands: Commands, time: Res<Time>, mut hints: Query<(Entity, &mut Hint)>) {
+    for (entity, mut hint) in &mut hints {
+        if hint.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
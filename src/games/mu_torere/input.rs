@@ -1,88 +1,840 @@
 //! Player input handling for Mu Torere.
+//!
+//! Pieces and board nodes are pickable, so mouse and touch input both arrive
+//! as [`Pointer`] events, and UI drawn over the board (pause menu, game over
+//! screen) blocks them. A mouse click moves a piece straight away, while a
+//! tap selects it first and a second tap (on the piece or its destination)
+//! moves it, and clicking a piece that can't move says why (see
+//! [`hint`](super::hint)). The board can also be played from the keyboard or a gamepad
+//! through the [`BoardInput`]s recorded by the gameplay screen.
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
-use crate::PausableSystems;
+use crate::{
+    games::two_player::{
+        board::{set_material_color, PIECE_RADIUS},
+        GameMode,
+    },
+    screens::{
+        gameplay::{BoardAction, BoardFocus, BoardInput},
+        Screen,
+    },
+    theme::{accessibility::Accessibility, themes::Theme},
+    AppSystems, PausableSystems, Pause,
+};
 
 use super::{
-    animation::{MoveEvent, MovingPiece},
-    board::{get_valid_moves, Piece, PIECE_RADIUS},
+    animation::{MoveEvent, MovingPiece, ReturningPiece},
+    board::{
+        board_position, get_valid_moves, BoardNode, BoardSize, DestinationMarker, FocusRing,
+        MovePreview, MoveRejection, MoveRules, Piece, Rules, TouchTarget, FOCUS_RING_Z,
+    },
+    hint::MoveRejected,
     is_playing_mu_torere,
-    state::{GameMode, GameSettings, GameState, PieceColor},
+    sound::PlaySound,
+    state::{GameState, PieceColor, RestartGame},
+    style::BoardAssets,
+    GAME_ID,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PieceDrag>();
+    app.init_resource::<PieceFocus>();
+    app.init_resource::<TouchMode>();
+    app.init_resource::<AllowedMoves>();
+    app.add_systems(
+        OnEnter(Screen::Playing(GAME_ID)),
+        (reset_piece_drag, reset_piece_focus, reset_allowed_moves),
+    );
+    app.add_systems(
+        Update,
+        (reset_piece_drag, reset_piece_focus)
+            .run_if(is_playing_mu_torere.and(on_message::<RestartGame>)),
+    );
     app.add_systems(
         Update,
-        handle_click
-            .run_if(is_playing_mu_torere)
-            .in_set(PausableSystems),
+        (
+            handle_board_actions
+                .in_set(AppSystems::Update)
+                .in_set(PausableSystems),
+            update_node_colors,
+            update_move_previews,
+            (detect_touch_mode, resize_touch_targets).chain(),
+            update_focus_ring.after(handle_board_actions),
+        )
+            .run_if(is_playing_mu_torere),
     );
+
+    app.add_observer(mark_hovered)
+        .add_observer(unmark_hovered)
+        .add_observer(click_piece)
+        .add_observer(click_node)
+        .add_observer(start_drag)
+        .add_observer(drag_piece)
+        .add_observer(drop_piece);
 }
 
-fn handle_click(
-    mouse: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform)>,
-    pieces: Query<(Entity, &Piece, &Transform)>,
+/// How far (in screen pixels) the pointer must travel while pressed before a
+/// press turns into a drag rather than a click.
+const DRAG_THRESHOLD: f32 = 6.0;
+/// Fingers wobble more than a mouse, so touch drags need to go further.
+const TOUCH_DRAG_THRESHOLD: f32 = 14.0;
+/// How much bigger than normal pieces and nodes are to hit in touch mode.
+const TOUCH_TARGET_SCALE: f32 = 1.6;
+/// The smallest a touch target gets on screen in touch mode, in logical pixels.
+const MIN_TOUCH_TARGET_SIZE: f32 = 44.0;
+/// How close to a legal destination a dragged piece must be dropped to snap onto it.
+const SNAP_DISTANCE: f32 = PIECE_RADIUS * 1.5;
+/// Z used for a piece while it is being dragged, so it draws above the others.
+const DRAG_Z: f32 = 3.0;
+
+/// Marks a piece or board node that a pointer is currently over.
+#[derive(Component)]
+pub struct Hovered;
+
+/// The piece currently held down by a pointer, if any.
+#[derive(Resource, Default)]
+struct PieceDrag(Option<HeldPiece>);
+
+struct HeldPiece {
+    entity: Entity,
+    /// Where the piece was resting when it was picked up.
+    origin: Vec2,
+    /// Offset from the pointer to the piece's centre, so it doesn't jump when grabbed.
+    grab_offset: Vec2,
+    is_dragging: bool,
+    destinations: Vec<usize>,
+}
+
+/// The piece focused from the keyboard, a gamepad, a tap or a hint and, once
+/// it has been selected, the destination focused for it.
+#[derive(Resource, Default)]
+pub(super) struct PieceFocus {
+    pub piece: Option<Entity>,
+    pub destination: Option<usize>,
+}
+
+/// Limits the local player to some of the legal moves, e.g. to the one a
+/// tutorial step teaches. `None` allows every legal move.
+#[derive(Resource, Default, PartialEq)]
+pub(super) struct AllowedMoves(pub Option<Vec<(usize, usize)>>);
+
+impl AllowedMoves {
+    pub fn allows(&self, from: usize, to: usize) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|moves| moves.contains(&(from, to)))
+    }
+
+    /// Returns true if any allowed move starts from `from`.
+    pub fn allows_from(&self, from: usize) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|moves| moves.iter().any(|&(start, _)| start == from))
+    }
+}
+
+/// Whether the player is using a touch screen, which enlarges the hit targets.
+/// Switches on with the first touch and back off when the mouse is clicked.
+#[derive(Resource, Default)]
+struct TouchMode(bool);
+
+fn reset_piece_drag(mut drag: ResMut<PieceDrag>) {
+    drag.0 = None;
+}
+
+fn reset_piece_focus(mut focus: ResMut<PieceFocus>) {
+    *focus = PieceFocus::default();
+}
+
+fn reset_allowed_moves(mut allowed: ResMut<AllowedMoves>) {
+    allowed.0 = None;
+}
+
+/// Returns the legal destinations of `piece` if the local player may move it right now.
+pub(super) fn movable_destinations(
+    rules: MoveRules,
+    piece: &Piece,
+    game_state: &GameState,
+    pause: &State<Pause>,
+    moving_pieces: &Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: &AllowedMoves,
+    pieces: &Query<(&Piece, &Children)>,
+) -> Vec<usize> {
+    if game_state.is_over()
+        || pause.get().0
+        || !moving_pieces.is_empty()
+        || !game_state.is_human_turn()
+        || piece.color != game_state.turn()
+    {
+        return Vec::new();
+    }
+    get_valid_moves(rules, piece, pieces)
+        .into_iter()
+        .filter(|&to| allowed.allows(piece.node_index, to))
+        .collect()
+}
+
+/// Why the local player can't move `piece`, or any piece if none was picked,
+/// if they would expect to be able to. Nothing is said while the game is over
+/// or paused, or a piece is moving.
+fn move_rejection<'a>(
+    rules: MoveRules,
+    piece: Option<&Piece>,
+    game_state: &GameState,
+    pause: &State<Pause>,
+    moving_pieces: &Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: &AllowedMoves,
+    pieces: impl IntoIterator<Item = &'a Piece>,
+) -> Option<MoveRejection> {
+    if game_state.is_over() || pause.get().0 || !moving_pieces.is_empty() {
+        return None;
+    }
+    if !game_state.is_human_turn() {
+        return Some(MoveRejection::ComputerThinking);
+    }
+    // A player always has a legal move on their turn, so only the allowed
+    // moves can stop them.
+    let Some(piece) = piece else {
+        return Some(MoveRejection::NotAllowed);
+    };
+    if piece.color != game_state.turn() {
+        return Some(MoveRejection::NotYourTurn);
+    }
+    let position = board_position(rules.size, game_state.turn(), pieces);
+    rules
+        .rejection(&position.nodes, piece.node_index)
+        .or_else(|| (!allowed.allows_from(piece.node_index)).then_some(MoveRejection::NotAllowed))
+}
+
+fn mark_hovered(
+    over: On<Pointer<Over>>,
+    mut commands: Commands,
+    targets: Query<(), Or<(With<Piece>, With<BoardNode>)>>,
+) {
+    if targets.contains(over.entity) {
+        commands.entity(over.entity).insert(Hovered);
+    }
+}
+
+fn unmark_hovered(
+    out: On<Pointer<Out>>,
+    mut commands: Commands,
+    targets: Query<(), Or<(With<Piece>, With<BoardNode>)>>,
+) {
+    if targets.contains(out.entity) {
+        commands.entity(out.entity).remove::<Hovered>();
+    }
+}
+
+fn click_piece(
+    click: On<Pointer<Click>>,
+    pieces: Query<&Piece>,
     pieces_for_validation: Query<(&Piece, &Children)>,
+    rules: Rules,
     game_state: Res<GameState>,
-    settings: Res<GameSettings>,
-    moving_pieces: Query<&MovingPiece>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    mut drag: ResMut<PieceDrag>,
+    mut focus: ResMut<PieceFocus>,
     mut move_events: MessageWriter<MoveEvent>,
+    mut rejections: MessageWriter<MoveRejected>,
 ) {
-    if game_state.game_over {
+    if click.button != PointerButton::Primary {
         return;
     }
-
-    if !moving_pieces.is_empty() {
+    let Ok(piece) = pieces.get(click.entity) else {
+        return;
+    };
+    // A click is also reported at the end of a drag; the drop handles that case.
+    if drag.0.as_ref().is_some_and(|held| held.is_dragging) {
         return;
     }
+    drag.0 = None;
 
-    if !mouse.just_pressed(MouseButton::Left) {
+    let destinations = movable_destinations(
+        rules.current(),
+        piece,
+        &game_state,
+        &pause,
+        &moving_pieces,
+        &allowed,
+        &pieces_for_validation,
+    );
+    let Some(&target_node) = destinations.first() else {
+        let reason = move_rejection(
+            rules.current(),
+            Some(piece),
+            &game_state,
+            &pause,
+            &moving_pieces,
+            &allowed,
+            pieces.iter(),
+        );
+        if let Some(reason) = reason {
+            rejections.write(MoveRejected {
+                piece: Some(click.entity),
+                reason,
+            });
+        }
+        return;
+    };
+    // A tap only selects the piece, so a stray touch can't make a move.
+    if click.pointer_id.is_touch() && focus.piece != Some(click.entity) {
+        *focus = PieceFocus {
+            piece: Some(click.entity),
+            destination: None,
+        };
         return;
     }
+    // A plain click (or a second tap) moves the piece to its first legal destination.
+    move_events.write(MoveEvent {
+        piece_entity: click.entity,
+        target_node,
+    });
+    *focus = PieceFocus::default();
+}
 
-    let Ok(window) = windows.single() else {
+fn click_node(
+    click: On<Pointer<Click>>,
+    nodes: Query<&BoardNode>,
+    pieces: Query<(Entity, &Piece)>,
+    pieces_for_validation: Query<(&Piece, &Children)>,
+    rules: Rules,
+    game_state: Res<GameState>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    mut focus: ResMut<PieceFocus>,
+    mut move_events: MessageWriter<MoveEvent>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    let Ok(node) = nodes.get(click.entity) else {
         return;
     };
 
-    let Some(cursor_pos) = window.cursor_position() else {
+    // Clicking an empty node moves the selected piece there or, with nothing
+    // selected, the only piece that can reach it (if there is exactly one).
+    let mut candidates = pieces.iter().filter(|(entity, piece)| {
+        focus.piece.is_none_or(|focused| focused == *entity)
+            && movable_destinations(
+                rules.current(),
+                piece,
+                &game_state,
+                &pause,
+                &moving_pieces,
+                &allowed,
+                &pieces_for_validation,
+            )
+            .contains(&node.index)
+    });
+    if let (Some((entity, _)), None) = (candidates.next(), candidates.next()) {
+        move_events.write(MoveEvent {
+            piece_entity: entity,
+            target_node: node.index,
+        });
+        *focus = PieceFocus::default();
+    }
+}
+
+fn start_drag(
+    drag_start: On<Pointer<DragStart>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    pieces: Query<(&Piece, &Transform)>,
+    pieces_for_validation: Query<(&Piece, &Children)>,
+    rules: Rules,
+    game_state: Res<GameState>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    mut drag: ResMut<PieceDrag>,
+) {
+    if drag_start.button != PointerButton::Primary {
+        return;
+    }
+    let Ok((piece, transform)) = pieces.get(drag_start.entity) else {
         return;
     };
+    let destinations = movable_destinations(
+        rules.current(),
+        piece,
+        &game_state,
+        &pause,
+        &moving_pieces,
+        &allowed,
+        &pieces_for_validation,
+    );
+    if destinations.is_empty() {
+        return;
+    }
 
-    let Ok((camera, camera_transform)) = cameras.single() else {
+    let (camera, camera_transform) = *camera;
+    let Ok(world_pos) =
+        camera.viewport_to_world_2d(camera_transform, drag_start.pointer_location.position)
+    else {
         return;
     };
+    let piece_pos = transform.translation.truncate();
+    drag.0 = Some(HeldPiece {
+        entity: drag_start.entity,
+        origin: piece_pos,
+        grab_offset: piece_pos - world_pos,
+        is_dragging: false,
+        destinations,
+    });
+}
 
-    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
+fn drag_piece(
+    drag_event: On<Pointer<Drag>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut transforms: Query<&mut Transform>,
+    pause: Res<State<Pause>>,
+    mut drag: ResMut<PieceDrag>,
+) {
+    let Some(held) = &mut drag.0 else {
         return;
     };
+    if held.entity != drag_event.entity || pause.get().0 {
+        return;
+    }
+    let threshold = if drag_event.pointer_id.is_touch() {
+        TOUCH_DRAG_THRESHOLD
+    } else {
+        DRAG_THRESHOLD
+    };
+    if !held.is_dragging && drag_event.distance.length() > threshold {
+        held.is_dragging = true;
+    }
+    if !held.is_dragging {
+        return;
+    }
 
-    let current_turn = game_state.current_turn;
-    let is_player_turn = match settings.mode {
-        GameMode::VsPlayer => true,
-        GameMode::VsComputer => current_turn == PieceColor::White,
+    let (camera, camera_transform) = *camera;
+    if let Ok(world_pos) =
+        camera.viewport_to_world_2d(camera_transform, drag_event.pointer_location.position)
+        && let Ok(mut transform) = transforms.get_mut(held.entity)
+    {
+        transform.translation = (world_pos + held.grab_offset).extend(DRAG_Z);
+    }
+}
+
+fn drop_piece(
+    drag_end: On<Pointer<DragEnd>>,
+    mut commands: Commands,
+    size: Res<BoardSize>,
+    transforms: Query<&Transform>,
+    pause: Res<State<Pause>>,
+    mut drag: ResMut<PieceDrag>,
+    mut move_events: MessageWriter<MoveEvent>,
+    mut sounds: MessageWriter<PlaySound>,
+) {
+    if drag
+        .0
+        .as_ref()
+        .is_none_or(|held| held.entity != drag_end.entity)
+    {
+        return;
+    }
+    let Some(held) = drag.0.take() else {
+        return;
     };
+    if !held.is_dragging {
+        return;
+    }
 
-    if !is_player_turn {
+    let Ok(transform) = transforms.get(held.entity) else {
         return;
+    };
+    let drop_pos = transform.translation.truncate();
+    // Pieces dropped while the game is paused always go back where they came from.
+    let target = held
+        .destinations
+        .iter()
+        .copied()
+        .map(|node| (node, size.node_position(node).distance(drop_pos)))
+        .filter(|&(_, distance)| distance < SNAP_DISTANCE && !pause.get().0)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    match target {
+        Some((target_node, _)) => {
+            move_events.write(MoveEvent {
+                piece_entity: held.entity,
+                target_node,
+            });
+        }
+        None => {
+            // Putting a piece back where it was is fine; anywhere else wasn't a legal move.
+            if drop_pos.distance(held.origin) >= SNAP_DISTANCE {
+                sounds.write(PlaySound::Illegal);
+            }
+            commands.entity(held.entity).insert(ReturningPiece {
+                start_pos: drop_pos,
+                end_pos: held.origin,
+                progress: 0.0,
+            });
+        }
+    }
+}
+
+fn update_node_colors(
+    drag: Res<PieceDrag>,
+    rules: Rules,
+    allowed: Res<AllowedMoves>,
+    focus: Res<PieceFocus>,
+    pieces: Query<(&Piece, &Children)>,
+    nodes: Query<(
+        &BoardNode,
+        &MeshMaterial2d<ColorMaterial>,
+        &Children,
+        Has<Hovered>,
+    )>,
+    mut markers: Query<&mut Visibility, With<DestinationMarker>>,
+    theme: Res<Theme>,
+    accessibility: Res<Accessibility>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let focused_destinations = focus
+        .piece
+        .and_then(|entity| pieces.get(entity).ok())
+        .map(|(piece, _)| {
+            get_valid_moves(rules.current(), piece, &pieces)
+                .into_iter()
+                .filter(|&to| allowed.allows(piece.node_index, to))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for (node, material, children, hovered) in &nodes {
+        let is_destination = focused_destinations.contains(&node.index)
+            || drag
+                .0
+                .as_ref()
+                .is_some_and(|held| held.is_dragging && held.destinations.contains(&node.index));
+        let color = if is_destination {
+            theme.board.node_destination
+        } else if hovered {
+            theme.board.node_hovered
+        } else {
+            theme.board.node
+        };
+        set_material_color(&mut materials, &material.0, color);
+
+        let marker_visibility = if is_destination && accessibility.shape_highlights {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        let mut markers = markers.iter_many_mut(children);
+        while let Some(mut visibility) = markers.fetch_next() {
+            visibility.set_if_neq(marker_visibility);
+        }
+    }
+}
+
+/// Shows a faint copy of the hovered piece on each node it can move to. Not
+/// while it's being dragged, as the destinations are lit up then.
+fn update_move_previews(
+    rules: Rules,
+    game_state: Res<GameState>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    drag: Res<PieceDrag>,
+    hovered: Query<&Piece, With<Hovered>>,
+    pieces: Query<(&Piece, &Children)>,
+    nodes: Query<(&BoardNode, &Children)>,
+    mut previews: Query<(&mut MeshMaterial2d<ColorMaterial>, &mut Visibility), With<MovePreview>>,
+    board_assets: Res<BoardAssets>,
+) {
+    let preview = hovered
+        .iter()
+        .next()
+        .filter(|_| drag.0.as_ref().is_none_or(|held| !held.is_dragging))
+        .map(|piece| {
+            let destinations = movable_destinations(
+                rules.current(),
+                piece,
+                &game_state,
+                &pause,
+                &moving_pieces,
+                &allowed,
+                &pieces,
+            );
+            (piece.color, destinations)
+        });
+
+    for (node, children) in &nodes {
+        let color = preview
+            .as_ref()
+            .filter(|(_, destinations)| destinations.contains(&node.index))
+            .map(|&(color, _)| color);
+        let mut previews = previews.iter_many_mut(children);
+        while let Some((mut material, mut visibility)) = previews.fetch_next() {
+            match color {
+                Some(color) => {
+                    material.set_if_neq(MeshMaterial2d(board_assets.ghost(color)));
+                    visibility.set_if_neq(Visibility::Inherited);
+                }
+                None => {
+                    visibility.set_if_neq(Visibility::Hidden);
+                }
+            }
+        }
+    }
+}
+
+fn handle_board_actions(
+    mut inputs: MessageReader<BoardInput>,
+    pieces: Query<(Entity, &Piece)>,
+    pieces_for_validation: Query<(&Piece, &Children)>,
+    rules: Rules,
+    game_state: Res<GameState>,
+    mode: Res<GameMode>,
+    pause: Res<State<Pause>>,
+    moving_pieces: Query<(), Or<(With<MovingPiece>, With<ReturningPiece>)>>,
+    allowed: Res<AllowedMoves>,
+    mut focus: ResMut<PieceFocus>,
+    mut board_focus: ResMut<BoardFocus>,
+    mut move_events: MessageWriter<MoveEvent>,
+    mut rejections: MessageWriter<MoveRejected>,
+) {
+    // The current player's movable pieces in board order, with their destinations.
+    let mut movable: Vec<(Entity, usize, Vec<usize>)> = pieces
+        .iter()
+        .filter_map(|(entity, piece)| {
+            let destinations = movable_destinations(
+                rules.current(),
+                piece,
+                &game_state,
+                &pause,
+                &moving_pieces,
+                &allowed,
+                &pieces_for_validation,
+            );
+            (!destinations.is_empty()).then_some((entity, piece.node_index, destinations))
+        })
+        .collect();
+    movable.sort_by_key(|&(_, node, _)| node);
+
+    // Drop the focus once its piece can no longer move (e.g. after a mouse move).
+    if focus
+        .piece
+        .is_some_and(|focused| !movable.iter().any(|&(entity, ..)| entity == focused))
+    {
+        *focus = PieceFocus::default();
     }
+    let destinations_of = |entity: Entity| {
+        movable
+            .iter()
+            .find(|&&(e, ..)| e == entity)
+            .map(|(_, _, destinations)| destinations.as_slice())
+            .unwrap_or_default()
+    };
+
+    for input in inputs.read() {
+        // In hot-seat games each seated gamepad only plays its own colour.
+        if let Some(seat) = input.seat
+            && *mode == GameMode::VsPlayer
+            && seat_color(seat) != game_state.turn()
+        {
+            if input.action == BoardAction::Confirm && !game_state.is_over() {
+                rejections.write(MoveRejected {
+                    piece: None,
+                    reason: MoveRejection::NotYourTurn,
+                });
+            }
+            continue;
+        }
+        let reject = |rejections: &mut MessageWriter<MoveRejected>, entity: Option<Entity>| {
+            let piece = entity.and_then(|entity| pieces.get(entity).ok().map(|(_, piece)| piece));
+            let reason = move_rejection(
+                rules.current(),
+                piece,
+                &game_state,
+                &pause,
+                &moving_pieces,
+                &allowed,
+                pieces.iter().map(|(_, piece)| piece),
+            );
+            if let Some(reason) = reason {
+                rejections.write(MoveRejected {
+                    piece: entity,
+                    reason,
+                });
+            }
+        };
 
-    // Find clicked piece and move it if it has a valid move
-    for (entity, piece, transform) in &pieces {
-        let piece_pos = transform.translation.truncate();
-        if world_pos.distance(piece_pos) < PIECE_RADIUS + 5.0 {
-            if piece.color == current_turn {
-                let valid_moves = get_valid_moves(piece, &pieces_for_validation);
-                if let Some(&target_node) = valid_moves.first() {
+        match input.action {
+            BoardAction::Next | BoardAction::Previous => {
+                let forward = input.action == BoardAction::Next;
+                match (focus.piece, focus.destination) {
+                    (Some(piece), Some(destination)) => {
+                        focus.destination =
+                            cycle(destinations_of(piece), Some(destination), forward);
+                    }
+                    _ => {
+                        let entities: Vec<Entity> = movable.iter().map(|&(e, ..)| e).collect();
+                        focus.piece = cycle(&entities, focus.piece, forward);
+                    }
+                }
+            }
+            BoardAction::FocusNode(_) | BoardAction::FocusCenter => {
+                let Some(node) = focused_node(rules.current().size, input.action) else {
+                    continue;
+                };
+                match (focus.piece, focus.destination) {
+                    (Some(piece), Some(_)) if destinations_of(piece).contains(&node) => {
+                        focus.destination = Some(node);
+                    }
+                    _ => {
+                        if let Some(&(entity, ..)) = movable.iter().find(|&&(_, n, _)| n == node) {
+                            focus.piece = Some(entity);
+                            focus.destination = None;
+                        } else if let Some((entity, _)) =
+                            pieces.iter().find(|(_, piece)| piece.node_index == node)
+                        {
+                            // Focusing a piece that can't move says why, as clicking it does.
+                            reject(&mut rejections, Some(entity));
+                        }
+                    }
+                }
+            }
+            BoardAction::Confirm => match (focus.piece, focus.destination) {
+                (Some(piece), Some(target_node)) => {
                     move_events.write(MoveEvent {
-                        piece_entity: entity,
+                        piece_entity: piece,
                         target_node,
                     });
-                    return;
+                    *focus = PieceFocus::default();
+                }
+                (Some(piece), None) => match destinations_of(piece) {
+                    // With only one place to go there is nothing to choose.
+                    &[target_node] => {
+                        move_events.write(MoveEvent {
+                            piece_entity: piece,
+                            target_node,
+                        });
+                        *focus = PieceFocus::default();
+                    }
+                    destinations => focus.destination = destinations.first().copied(),
+                },
+                (None, _) => match movable.first() {
+                    Some(&(entity, ..)) => focus.piece = Some(entity),
+                    None => reject(&mut rejections, None),
+                },
+            },
+            BoardAction::Cancel => {
+                if focus.destination.is_some() {
+                    focus.destination = None;
+                } else {
+                    focus.piece = None;
                 }
             }
         }
     }
+
+    board_focus.0 = focus.piece.is_some();
+}
+
+/// The node a focus action names on a board of the given size: the kewai
+/// numbered like the key, or the pūtahi. Keys past the last kewai name nothing.
+fn focused_node(size: BoardSize, action: BoardAction) -> Option<usize> {
+    match action {
+        BoardAction::FocusNode(kewai) => (kewai < size.kewai).then_some(kewai),
+        BoardAction::FocusCenter => Some(size.center()),
+        _ => None,
+    }
+}
+
+/// The colour played by a gamepad in the given seat.
+fn seat_color(seat: usize) -> PieceColor {
+    if seat.is_multiple_of(2) {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    }
+}
+
+/// Returns the item after (or before) `current` in `items`, wrapping around.
+/// With nothing current, starts from the first (or last) item.
+fn cycle<T: Copy + PartialEq>(items: &[T], current: Option<T>, forward: bool) -> Option<T> {
+    if items.is_empty() {
+        return None;
+    }
+    let index = match current.and_then(|c| items.iter().position(|&item| item == c)) {
+        Some(i) if forward => (i + 1) % items.len(),
+        Some(i) => (i + items.len() - 1) % items.len(),
+        None if forward => 0,
+        None => items.len() - 1,
+    };
+    Some(items[index])
+}
+
+fn update_focus_ring(
+    size: Res<BoardSize>,
+    focus: Res<PieceFocus>,
+    theme: Res<Theme>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    pieces: Query<&Transform, (With<Piece>, Without<FocusRing>)>,
+    ring: Single<
+        (
+            &mut Transform,
+            &mut Visibility,
+            &MeshMaterial2d<ColorMaterial>,
+        ),
+        With<FocusRing>,
+    >,
+) {
+    let (mut transform, mut visibility, material) = ring.into_inner();
+    set_material_color(&mut materials, &material.0, theme.board.focus_ring);
+    let position = match (focus.piece, focus.destination) {
+        (_, Some(node)) => Some(size.node_position(node)),
+        (Some(piece), None) => pieces.get(piece).ok().map(|t| t.translation.truncate()),
+        (None, None) => None,
+    };
+    match position {
+        Some(position) => {
+            transform.translation = position.extend(FOCUS_RING_Z);
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+fn detect_touch_mode(
+    touches: Res<Touches>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut touch_mode: ResMut<TouchMode>,
+) {
+    if touches.any_just_pressed() && !touch_mode.0 {
+        touch_mode.0 = true;
+    } else if mouse.get_just_pressed().next().is_some() && touch_mode.0 {
+        touch_mode.0 = false;
+    }
+}
+
+fn resize_touch_targets(
+    touch_mode: Res<TouchMode>,
+    projection: Single<&Projection, With<Camera2d>>,
+    mut targets: Query<(&TouchTarget, &mut Sprite)>,
+) {
+    let (scale, min_size) = if touch_mode.0 {
+        // The board shrinks on small screens, but fingers don't.
+        let world_per_pixel = match *projection {
+            Projection::Orthographic(orthographic) => orthographic.scale,
+            _ => 1.0,
+        };
+        (TOUCH_TARGET_SCALE, MIN_TOUCH_TARGET_SIZE * world_per_pixel)
+    } else {
+        (1.0, 0.0)
+    };
+    for (target, mut sprite) in &mut targets {
+        let size = Some(Vec2::splat((target.base_size * scale).max(min_size)));
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+    }
 }
@@ -1,33 +1,126 @@
 //! Mū Tōrere - Traditional Māori strategy game.
 //!
-//! A two-player abstract strategy game played on a board with 8 outer positions
+//! A two-player abstract strategy game played on a board with outer positions
 //! (kewai) arranged in a circle, connected to a central position (pūtahi).
+//! The traditional board has 8 kewai; 6, 10 and 12 can be chosen too, and
+//! games can start from a custom position set up in the editor. There are
+//! puzzles too, and a tutorial that teaches the rules.
 
+mod accessibility;
 mod animation;
 mod board;
 mod computer;
+mod editor;
+mod hint;
 mod input;
+mod puzzle;
+mod solver;
+mod sound;
 pub mod state;
-mod ui;
+mod style;
+mod tutorial;
 
 use bevy::prelude::*;
 
-pub use state::{GameMode, GameSettings};
+use board::BoardSize;
+use state::{BlockedRule, GameSettings, PutahiRule};
 
-use crate::screens::{ActiveGame, Screen};
+use crate::{
+    games::{
+        registry::{Game, GameId, GameOption, GameSetting, OptionAction, RegisterGame},
+        two_player,
+    },
+    menus::{Menu, SelectedGame},
+    screens::Screen,
+};
+
+pub const GAME_ID: GameId = GameId("mu_torere");
 
 /// Returns true if Mu Torere is the active game being played
 pub fn is_playing_mu_torere(screen: Res<State<Screen>>) -> bool {
-    *screen.get() == Screen::Playing(ActiveGame::MuTorere)
+    *screen.get() == Screen::Playing(GAME_ID)
+}
+
+/// Returns true if Mu Torere is the game chosen in the menus, for its own menus.
+fn is_mu_torere_selected(selected: Res<SelectedGame>) -> bool {
+    selected.game == Some(GAME_ID)
 }
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_game(Game {
+        id: GAME_ID,
+        name: "Mū Tōrere",
+        options: two_player::mode_options()
+            .into_iter()
+            .chain([
+                GameOption {
+                    label: "Tutorial",
+                    action: OptionAction::Start(tutorial::start_tutorial),
+                },
+                GameOption {
+                    label: "Custom Start",
+                    action: OptionAction::Open(Menu::StartEditor),
+                },
+                GameOption {
+                    label: "Puzzles",
+                    action: OptionAction::Open(Menu::Puzzles),
+                },
+            ])
+            .collect(),
+        settings: vec![
+            GameSetting {
+                label: "Board Size",
+                value: |world| world.resource::<BoardSize>().label(),
+                step: |world, step| {
+                    let mut size = world.resource_mut::<BoardSize>();
+                    *size = step_choice(&BoardSize::ALL, *size, step);
+                },
+            },
+            GameSetting {
+                label: "Pūtahi Rule",
+                value: |world| {
+                    let rules = world.resource::<GameSettings>().rules;
+                    rules.putahi.label().to_string()
+                },
+                step: |world, step| {
+                    let rules = &mut world.resource_mut::<GameSettings>().rules;
+                    rules.putahi = step_choice(&PutahiRule::ALL, rules.putahi, step);
+                },
+            },
+            GameSetting {
+                label: "When Blocked",
+                value: |world| {
+                    let rules = world.resource::<GameSettings>().rules;
+                    rules.blocked.label().to_string()
+                },
+                step: |world, step| {
+                    let rules = &mut world.resource_mut::<GameSettings>().rules;
+                    rules.blocked = step_choice(&BlockedRule::ALL, rules.blocked, step);
+                },
+            },
+        ],
+    });
     app.add_plugins((
+        accessibility::plugin,
         animation::plugin,
         board::plugin,
         computer::plugin,
+        editor::plugin,
+        hint::plugin,
         input::plugin,
+        puzzle::plugin,
+        sound::plugin,
         state::plugin,
-        ui::plugin,
+        style::plugin,
+        tutorial::plugin,
     ));
 }
+
+/// The choice `step` places after `current` in `all`, wrapping around.
+fn step_choice<T: Copy + PartialEq>(all: &[T], current: T, step: isize) -> T {
+    let index = all
+        .iter()
+        .position(|&choice| choice == current)
+        .unwrap_or(0);
+    all[(index as isize + step).rem_euclid(all.len() as isize) as usize]
+}
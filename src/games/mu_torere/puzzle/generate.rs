@@ -0,0 +1,107 @@
+//! Regenerates the puzzle pack from the solver, when the game is run with
+//! `--generate-puzzles`, then exits.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+
+use super::{
+    super::{
+        board::BoardSize,
+        solver::{Outcome, Solution},
+        state::{PieceColor, StartPosition},
+    },
+    move_rules, Puzzle, PuzzlePack, PACK_PATH,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Startup,
+        generate_puzzles.run_if(|| std::env::args().any(|arg| arg == "--generate-puzzles")),
+    );
+}
+
+/// The most puzzles kept for each board size and number of moves.
+const PUZZLES_PER_GROUP: usize = 6;
+
+fn generate_puzzles(mut app_exit: MessageWriter<AppExit>) {
+    let pack = PuzzlePack {
+        puzzles: BoardSize::ALL.into_iter().flat_map(generate).collect(),
+    };
+    let path = format!("{}/assets/{PACK_PATH}", env!("CARGO_MANIFEST_DIR"));
+    let result = ron::ser::to_string_pretty(&pack, default())
+        .map_err(|error| error.to_string())
+        .and_then(|text| std::fs::write(&path, text + "\n").map_err(|error| error.to_string()));
+    match result {
+        Ok(()) => {
+            info!("Wrote {} puzzles to {path}", pack.puzzles.len());
+            app_exit.write(AppExit::Success);
+        }
+        Err(error) => {
+            error!("Failed to write puzzles to {path}: {error}");
+            app_exit.write(AppExit::error());
+        }
+    }
+}
+
+/// Positions with White to move, a forced win, and only one move that keeps
+/// it, grouped by how many moves the win takes. Positions that are the same
+/// but for turning or flipping the board count once.
+fn generate(size: BoardSize) -> Vec<Puzzle> {
+    let solution = Solution::solve(move_rules(size));
+    let mut groups: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+    for (position, outcome) in solution.outcomes() {
+        let Outcome::Win(moves) = outcome else {
+            continue;
+        };
+        if position.to_move == PieceColor::White && solution.winning_moves(position).len() == 1 {
+            groups
+                .entry(moves)
+                .or_default()
+                .insert(canonical(size, position));
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(moves, positions)| {
+            positions
+                .into_iter()
+                .take(PUZZLES_PER_GROUP)
+                .map(move |position| Puzzle { position, moves })
+        })
+        .collect()
+}
+
+/// The first, alphabetically, of the ways to write the position after turning
+/// or flipping the board.
+fn canonical(size: BoardSize, position: &StartPosition) -> String {
+    let kewai = size.kewai;
+    (0..kewai)
+        .flat_map(|turn| {
+            [false, true].map(|flip| {
+                let mut nodes = position.nodes.clone();
+                for (from, &node) in position.nodes[..kewai].iter().enumerate() {
+                    let to = if flip {
+                        (turn + kewai - from) % kewai
+                    } else {
+                        (from + turn) % kewai
+                    };
+                    nodes[to] = node;
+                }
+                encode(&nodes)
+            })
+        })
+        .min()
+        .unwrap_or_else(|| encode(&position.nodes))
+}
+
+fn encode(nodes: &[Option<PieceColor>]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Some(PieceColor::White) => 'W',
+            Some(PieceColor::Black) => 'B',
+            None => '.',
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+//! The puzzle list, for one board size at a time, with the puzzles grouped by
+//! how many moves they take.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    gamepad::gamepad_just_pressed,
+    input_map::{action_just_pressed, InputAction},
+    menus::Menu,
+    theme::widget,
+};
+
+use super::{
+    super::{board::BoardSize, is_mu_torere_selected, step_choice},
+    start_puzzle, Puzzle, PuzzlePack, PuzzleProgress, Puzzles,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Menu::Puzzles),
+        spawn_puzzle_menu.run_if(is_mu_torere_selected),
+    );
+    app.add_systems(
+        Update,
+        (
+            fill_puzzle_list,
+            update_size_label,
+            go_back.run_if(
+                action_just_pressed(InputAction::Cancel)
+                    .or(gamepad_just_pressed(GamepadButton::East)),
+            ),
+        )
+            .run_if(in_state(Menu::Puzzles).and(is_mu_torere_selected)),
+    );
+}
+
+/// Holds a row for each number of moves. Filled once the puzzles have loaded,
+/// and again when the board size changes.
+#[derive(Component)]
+struct PuzzleList {
+    shown: Option<BoardSize>,
+}
+
+#[derive(Component)]
+struct SizeLabel;
+
+fn spawn_puzzle_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Puzzle Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Puzzles),
+        children![
+            widget::header("Puzzles"),
+            widget::label("White to move and block Black, however Black defends."),
+            (
+                Name::new("Board Size"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    widget::button_small("<", step_size(-1)),
+                    (
+                        Node {
+                            justify_content: JustifyContent::Center,
+                            min_width: Val::Px(160.0),
+                            ..default()
+                        },
+                        children![(widget::label(""), SizeLabel)],
+                    ),
+                    widget::button_small(">", step_size(1)),
+                ],
+            ),
+            (
+                Name::new("Puzzle List"),
+                PuzzleList { shown: None },
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Start,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![widget::label("Loading puzzles...")],
+            ),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn fill_puzzle_list(
+    mut commands: Commands,
+    list: Single<(Entity, &mut PuzzleList)>,
+    size: Res<BoardSize>,
+    puzzles: Option<Res<Puzzles>>,
+    packs: Res<Assets<PuzzlePack>>,
+    progress: Res<PuzzleProgress>,
+) {
+    let (entity, mut list) = list.into_inner();
+    let Some(pack) = puzzles.and_then(|puzzles| packs.get(&puzzles.pack)) else {
+        return;
+    };
+    if list.shown == Some(*size) {
+        return;
+    }
+    list.shown = Some(*size);
+
+    let puzzles: Vec<&Puzzle> = pack
+        .puzzles
+        .iter()
+        .filter(|puzzle| puzzle.size() == *size)
+        .collect();
+    let mut rows: Vec<(usize, Vec<Puzzle>)> = Vec::new();
+    for puzzle in puzzles {
+        match rows.last_mut() {
+            Some((moves, row)) if *moves == puzzle.moves => row.push(puzzle.clone()),
+            _ => rows.push((puzzle.moves, vec![puzzle.clone()])),
+        }
+    }
+    let rows: Vec<_> = rows
+        .into_iter()
+        .map(|(moves, row)| {
+            let solved = row
+                .iter()
+                .filter(|puzzle| progress.is_solved(puzzle))
+                .count();
+            (moves, solved, row)
+        })
+        .collect();
+
+    commands
+        .entity(entity)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            if rows.is_empty() {
+                parent.spawn(widget::label("No puzzles for this board."));
+            }
+            for (moves, solved, row) in rows {
+                parent.spawn(puzzle_row(moves, solved, row));
+            }
+        });
+}
+
+fn puzzle_row(moves: usize, solved: usize, puzzles: Vec<Puzzle>) -> impl Bundle {
+    let total = puzzles.len();
+    (
+        Name::new(format!("Win in {moves}")),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Node {
+                    min_width: Val::Px(140.0),
+                    ..default()
+                },
+                children![widget::label(format!("Win in {moves}"))],
+            ));
+            for (index, puzzle) in puzzles.into_iter().enumerate() {
+                parent.spawn(widget::button_small(
+                    (index + 1).to_string(),
+                    play_puzzle(puzzle),
+                ));
+            }
+            parent.spawn(widget::label(format!("{solved} of {total} solved")));
+        })),
+    )
+}
+
+fn play_puzzle(puzzle: Puzzle) -> impl Fn(On<Pointer<Click>>, Commands) {
+    move |_, mut commands| {
+        let puzzle = puzzle.clone();
+        commands.queue(move |world: &mut World| start_puzzle(world, puzzle));
+    }
+}
+
+fn step_size(step: isize) -> impl Fn(On<Pointer<Click>>, ResMut<BoardSize>) {
+    move |_, mut size| {
+        *size = step_choice(&BoardSize::ALL, *size, step);
+    }
+}
+
+fn update_size_label(size: Res<BoardSize>, mut label: Single<&mut Text, With<SizeLabel>>) {
+    let text = size.label();
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, next_menu: ResMut<NextState<Menu>>) {
+    go_back(next_menu);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::GameOptions);
+}
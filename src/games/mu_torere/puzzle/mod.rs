@@ -0,0 +1,382 @@
+//! Puzzles: positions where White can block Black within a set number of
+//! moves, however Black defends.
+//!
+//! The puzzles ship in `assets/puzzles`, generated from the [solver](super::solver)
+//! (run a dev build with `--generate-puzzles` to regenerate them). Each has
+//! exactly one winning first move. The computer defends as well as it can,
+//! and answers a wrong move with the reply that refutes it. Solved puzzles
+//! are saved between sessions.
+
+#[cfg(all(feature = "dev", not(target_family = "wasm")))]
+mod generate;
+mod menu;
+
+use std::collections::HashSet;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_tracking::{AssetGroup, LoadResource},
+    games::two_player::{GameMode, GameResult, SetUpMatch},
+    menus::{start_game, Menu},
+    persistence,
+    screens::{gameplay::HUD_FOOTER_HEIGHT, InGame, Screen},
+    theme::widget,
+};
+
+use super::{
+    board::{board_position, BoardSize, MoveRules, Piece},
+    is_playing_mu_torere,
+    solver::{Outcome, Solution},
+    state::{
+        stop_clocks, BlockedRule, CustomStart, GameSettings, GameState, PieceColor, PutahiRule,
+        RuleSet, SavedOptions, StartPosition,
+    },
+    GAME_ID,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<PuzzlePack>();
+    app.init_asset_loader::<PuzzlePackLoader>();
+    app.load_resource_in::<Puzzles>(AssetGroup::Game(GAME_ID));
+    app.insert_resource(PuzzleProgress::load());
+    app.add_systems(
+        OnEnter(Screen::MainMenu),
+        end_puzzle.run_if(resource_exists::<ActivePuzzle>),
+    );
+    app.add_systems(
+        OnEnter(Menu::GameSelect),
+        reopen_puzzle_list.run_if(resource_exists::<ReturnToPuzzles>),
+    );
+    app.add_systems(
+        OnEnter(Screen::Playing(GAME_ID)),
+        spawn_puzzle_bar.run_if(resource_exists::<ActivePuzzle>),
+    );
+    // Puzzles are counted in moves, not time.
+    app.add_systems(
+        OnEnter(InGame),
+        stop_clocks
+            .in_set(SetUpMatch)
+            .run_if(is_playing_mu_torere.and(resource_exists::<ActivePuzzle>)),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_puzzle_status,
+            (update_status_label, record_solved).after(update_puzzle_status),
+        )
+            .run_if(is_playing_mu_torere.and(resource_exists::<ActivePuzzle>)),
+    );
+    app.add_systems(
+        Update,
+        save_progress
+            .run_if(resource_changed::<PuzzleProgress>.and(not(resource_added::<PuzzleProgress>))),
+    );
+    #[cfg(all(feature = "dev", not(target_family = "wasm")))]
+    app.add_plugins(generate::plugin);
+    app.add_plugins(menu::plugin);
+}
+
+const PACK_PATH: &str = "puzzles/mu_torere.puzzles.ron";
+const SAVE_NAME: &str = "puzzle_progress";
+
+/// Puzzles are played under the traditional rules, whatever the options menu says.
+const PUZZLE_RULES: RuleSet = RuleSet {
+    putahi: PutahiRule::EnterAndLeave,
+    blocked: BlockedRule::Loses,
+};
+
+fn move_rules(size: BoardSize) -> MoveRules {
+    MoveRules {
+        size,
+        putahi: PUZZLE_RULES.putahi,
+        moves_played: 0,
+    }
+}
+
+/// White to move and win.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    /// What stands on each node, pūtahi last: `W` or `B` for a piece, `.` for empty.
+    /// Also identifies the puzzle in the saved progress.
+    pub position: String,
+    /// How many of White's moves the win takes against the best defence.
+    pub moves: usize,
+}
+
+impl Puzzle {
+    pub fn size(&self) -> BoardSize {
+        BoardSize {
+            kewai: self.position.chars().count() - 1,
+        }
+    }
+
+    pub fn start(&self) -> StartPosition {
+        StartPosition::from_text(&self.position, PieceColor::White)
+    }
+
+    /// Why the puzzle can't be played, if it can't.
+    fn validate(&self) -> Result<(), String> {
+        let size = self.size();
+        if !BoardSize::ALL.contains(&size) {
+            return Err(format!(
+                "{} has no board with {} kewai",
+                self.position, size.kewai
+            ));
+        }
+        for piece in ['W', 'B'] {
+            let count = self.position.chars().filter(|&node| node == piece).count();
+            if count != size.kewai / 2 {
+                return Err(format!("{} has {count} {piece} pieces", self.position));
+            }
+        }
+        if self.moves == 0 {
+            return Err(format!("{} is won in no moves", self.position));
+        }
+        Ok(())
+    }
+}
+
+/// Every puzzle, in order of board size and then moves.
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzlePack {
+    pub puzzles: Vec<Puzzle>,
+}
+
+#[derive(Default)]
+struct PuzzlePackLoader;
+
+impl AssetLoader for PuzzlePackLoader {
+    type Asset = PuzzlePack;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<PuzzlePack, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let pack: PuzzlePack = ron::de::from_bytes(&bytes)?;
+        for puzzle in &pack.puzzles {
+            puzzle.validate()?;
+        }
+        Ok(pack)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["puzzles.ron"]
+    }
+}
+
+#[derive(Resource, Asset, TypePath, Clone)]
+struct Puzzles {
+    #[dependency]
+    pack: Handle<PuzzlePack>,
+}
+
+impl FromWorld for Puzzles {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            pack: assets.load(PACK_PATH),
+        }
+    }
+}
+
+/// The puzzles that have been solved, by [`Puzzle::position`].
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct PuzzleProgress {
+    solved: HashSet<String>,
+}
+
+impl PuzzleProgress {
+    fn load() -> Self {
+        persistence::load(SAVE_NAME).unwrap_or_default()
+    }
+
+    fn is_solved(&self, puzzle: &Puzzle) -> bool {
+        self.solved.contains(&puzzle.position)
+    }
+}
+
+fn save_progress(progress: Res<PuzzleProgress>) {
+    persistence::save(SAVE_NAME, &*progress);
+}
+
+/// The puzzle being played, and the solution the computer defends it with.
+#[derive(Resource)]
+pub struct ActivePuzzle {
+    puzzle: Puzzle,
+    solution: Solution,
+    status: PuzzleStatus,
+    options: SavedOptions,
+}
+
+impl ActivePuzzle {
+    /// The computer's move: the best defence, or the refutation of a wrong move.
+    pub fn best_move(&self, position: &StartPosition) -> Option<(usize, usize)> {
+        self.solution.best_move(position)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PuzzleStatus {
+    /// White can still win within this many more moves.
+    Solving {
+        moves_left: usize,
+    },
+    Solved,
+    /// The last move let Black escape, or the game ended some other way.
+    Failed,
+}
+
+impl PuzzleStatus {
+    fn text(self) -> String {
+        match self {
+            PuzzleStatus::Solving { moves_left: 1 } => "Block Black this move.".to_string(),
+            PuzzleStatus::Solving { moves_left } => {
+                format!("Block Black within {moves_left} moves.")
+            }
+            PuzzleStatus::Solved => "Solved!".to_string(),
+            PuzzleStatus::Failed => "Black can escape now. Undo to try again.".to_string(),
+        }
+    }
+}
+
+/// Starts `puzzle` against the computer, on its board size and under the
+/// traditional rules, until it ends.
+fn start_puzzle(world: &mut World, puzzle: Puzzle) {
+    let size = puzzle.size();
+    let options = SavedOptions::replace(world, size, PUZZLE_RULES);
+    world.resource_mut::<CustomStart>().0 = Some(puzzle.start());
+    world.insert_resource(ActivePuzzle {
+        solution: Solution::solve(move_rules(size)),
+        status: PuzzleStatus::Solving {
+            moves_left: puzzle.moves,
+        },
+        puzzle,
+        options,
+    });
+    start_game(world, GAME_ID, |world| {
+        world.insert_resource(GameMode::VsComputer);
+    });
+}
+
+/// Puts back the options the puzzle played without.
+fn end_puzzle(
+    mut commands: Commands,
+    puzzle: Res<ActivePuzzle>,
+    mut size: ResMut<BoardSize>,
+    mut settings: ResMut<GameSettings>,
+) {
+    puzzle.options.restore(&mut size, &mut settings);
+    commands.remove_resource::<ActivePuzzle>();
+}
+
+fn update_puzzle_status(
+    mut puzzle: ResMut<ActivePuzzle>,
+    game_state: Res<GameState>,
+    size: Res<BoardSize>,
+    pieces: Query<&Piece>,
+) {
+    if game_state.is_moving() {
+        return;
+    }
+    // White moves first, and a blocked player loses, so the sides take turns.
+    let white_moves = game_state.moves_played().div_ceil(2);
+    let status = match game_state.result() {
+        Some(GameResult::Win {
+            winner: PieceColor::White,
+            ..
+        }) if white_moves <= puzzle.puzzle.moves => PuzzleStatus::Solved,
+        Some(_) => PuzzleStatus::Failed,
+        None => {
+            let moves_left = puzzle.puzzle.moves.saturating_sub(white_moves);
+            let position = board_position(*size, game_state.turn(), pieces);
+            let on_track = match (position.to_move, puzzle.solution.outcome(&position)) {
+                (PieceColor::White, Some(Outcome::Win(moves)))
+                | (PieceColor::Black, Some(Outcome::Loss(moves))) => moves <= moves_left,
+                _ => false,
+            };
+            if on_track {
+                PuzzleStatus::Solving { moves_left }
+            } else {
+                PuzzleStatus::Failed
+            }
+        }
+    };
+    if puzzle.status != status {
+        puzzle.status = status;
+    }
+}
+
+fn record_solved(puzzle: Res<ActivePuzzle>, mut progress: ResMut<PuzzleProgress>) {
+    if puzzle.status == PuzzleStatus::Solved && !progress.is_solved(&puzzle.puzzle) {
+        progress.solved.insert(puzzle.puzzle.position.clone());
+    }
+}
+
+#[derive(Component)]
+struct PuzzleStatusLabel;
+
+/// The puzzle's instructions, in the footer where the clocks go in other games,
+/// and a way back to the list.
+fn spawn_puzzle_bar(mut commands: Commands, puzzle: Res<ActivePuzzle>) {
+    commands.spawn((
+        Name::new("Puzzle Bar"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            height: Val::Px(HUD_FOOTER_HEIGHT),
+            width: Val::Percent(100.0),
+            padding: UiRect::horizontal(Val::Px(20.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        // Above the results screen, so the puzzle list can be reached from it.
+        GlobalZIndex(4),
+        Pickable::IGNORE,
+        StateScoped(Screen::Playing(GAME_ID)),
+        children![
+            (widget::label(puzzle.status.text()), PuzzleStatusLabel),
+            widget::button_medium("Puzzles", open_puzzle_list),
+        ],
+    ));
+}
+
+fn update_status_label(
+    puzzle: Res<ActivePuzzle>,
+    mut label: Single<&mut Text, With<PuzzleStatusLabel>>,
+) {
+    let text = puzzle.status.text();
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+/// Asks for the puzzle list once the main menu has opened, as it opens on the
+/// game select menu.
+#[derive(Resource)]
+struct ReturnToPuzzles;
+
+fn open_puzzle_list(
+    _: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.insert_resource(ReturnToPuzzles);
+    next_screen.set(Screen::MainMenu);
+}
+
+fn reopen_puzzle_list(mut commands: Commands, mut next_menu: ResMut<NextState<Menu>>) {
+    commands.remove_resource::<ReturnToPuzzles>();
+    next_menu.set(Menu::Puzzles);
+}
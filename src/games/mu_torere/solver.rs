@@ -0,0 +1,227 @@
+//! Solves Mū Tōrere by working back from the positions where the side to
+//! move is blocked.
+//!
+//! Even the 12-kewai board has only a few tens of thousands of positions, so
+//! every one of them is solved up front. Puzzles are generated from the
+//! solution, and the computer uses it to defend them.
+
+use std::collections::HashMap;
+
+use super::{
+    board::{BoardSize, MoveRules},
+    state::{PieceColor, StartPosition},
+};
+
+/// How a position ends with best play, counted in the moves of the side to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The side to move blocks the other side with its `n`th move.
+    Win(usize),
+    /// The side to move is blocked after the other side's `n`th move. `Loss(0)`
+    /// means it is blocked already.
+    Loss(usize),
+}
+
+/// The [`Outcome`] of every position on one board. Positions missing from it
+/// are draws: neither side can force a win.
+pub struct Solution {
+    rules: MoveRules,
+    outcomes: HashMap<StartPosition, Outcome>,
+}
+
+impl Solution {
+    /// Solves every position under `rules`, where a blocked player loses.
+    /// The number of moves played is ignored, so the opening pūtahi rule
+    /// can't be solved.
+    pub fn solve(rules: MoveRules) -> Self {
+        let positions = all_positions(rules.size);
+        let mut outcomes = HashMap::new();
+        // Each pass settles the positions one move further from the end, using
+        // only what earlier passes settled, so the counts are the shortest wins
+        // and longest losses.
+        loop {
+            let settled: Vec<(StartPosition, Outcome)> = positions
+                .iter()
+                .filter(|position| !outcomes.contains_key(*position))
+                .filter_map(|position| {
+                    let after: Vec<Option<Outcome>> = moves(rules, position)
+                        .into_iter()
+                        .map(|mv| outcomes.get(&play(position, mv)).copied())
+                        .collect();
+                    let shortest_win = after
+                        .iter()
+                        .filter_map(|outcome| match outcome {
+                            Some(Outcome::Loss(n)) => Some(n + 1),
+                            _ => None,
+                        })
+                        .min();
+                    let outcome = match shortest_win {
+                        Some(n) => Outcome::Win(n),
+                        None => {
+                            let mut longest_loss = 0;
+                            for outcome in &after {
+                                match outcome {
+                                    Some(Outcome::Win(n)) => longest_loss = longest_loss.max(*n),
+                                    _ => return None,
+                                }
+                            }
+                            Outcome::Loss(longest_loss)
+                        }
+                    };
+                    Some((position.clone(), outcome))
+                })
+                .collect();
+            if settled.is_empty() {
+                break;
+            }
+            outcomes.extend(settled);
+        }
+        Self { rules, outcomes }
+    }
+
+    /// The outcome of `position`, or `None` if it is a draw.
+    pub fn outcome(&self, position: &StartPosition) -> Option<Outcome> {
+        self.outcomes.get(position).copied()
+    }
+
+    /// Every position that isn't a draw, with its outcome.
+    #[cfg(all(feature = "dev", not(target_family = "wasm")))]
+    pub fn outcomes(&self) -> impl Iterator<Item = (&StartPosition, Outcome)> {
+        self.outcomes
+            .iter()
+            .map(|(position, outcome)| (position, *outcome))
+    }
+
+    /// The moves that leave the other side lost.
+    pub fn winning_moves(&self, position: &StartPosition) -> Vec<(usize, usize)> {
+        moves(self.rules, position)
+            .into_iter()
+            .filter(|&mv| matches!(self.outcome(&play(position, mv)), Some(Outcome::Loss(_))))
+            .collect()
+    }
+
+    /// The best move in `position`: the quickest win, else a draw, else the
+    /// longest resistance. `None` if the side to move is blocked.
+    pub fn best_move(&self, position: &StartPosition) -> Option<(usize, usize)> {
+        moves(self.rules, position).into_iter().max_by_key(|&mv| {
+            // Higher is better for the mover.
+            match self.outcome(&play(position, mv)) {
+                Some(Outcome::Loss(n)) => isize::MAX - n as isize,
+                None => 0,
+                Some(Outcome::Win(n)) => -(isize::MAX - n as isize),
+            }
+        })
+    }
+}
+
+/// Every legal move in `position`, as the nodes moved from and to.
+pub fn moves(rules: MoveRules, position: &StartPosition) -> Vec<(usize, usize)> {
+    (0..position.nodes.len())
+        .filter(|&from| position.nodes[from] == Some(position.to_move))
+        .flat_map(|from| {
+            rules
+                .destinations(&position.nodes, from)
+                .into_iter()
+                .map(move |to| (from, to))
+        })
+        .collect()
+}
+
+/// The position after `mv`, with the other side to move.
+pub fn play(position: &StartPosition, (from, to): (usize, usize)) -> StartPosition {
+    let mut nodes = position.nodes.clone();
+    nodes.swap(from, to);
+    StartPosition {
+        nodes,
+        to_move: position.to_move.opposite(),
+    }
+}
+
+/// Every position with half the kewai held by each side, with either side to move.
+fn all_positions(size: BoardSize) -> Vec<StartPosition> {
+    let mut positions = Vec::new();
+    for empty in 0..size.node_count() {
+        // Each subset of the other nodes, as a bit mask, is a choice of White's nodes.
+        let others: Vec<usize> = (0..size.node_count())
+            .filter(|&node| node != empty)
+            .collect();
+        for mask in 0u32..1 << others.len() {
+            if mask.count_ones() as usize != size.kewai / 2 {
+                continue;
+            }
+            let mut nodes = vec![None; size.node_count()];
+            for (bit, &node) in others.iter().enumerate() {
+                nodes[node] = Some(if mask & 1 << bit != 0 {
+                    PieceColor::White
+                } else {
+                    PieceColor::Black
+                });
+            }
+            for to_move in [PieceColor::White, PieceColor::Black] {
+                positions.push(StartPosition {
+                    nodes: nodes.clone(),
+                    to_move,
+                });
+            }
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::mu_torere::{puzzle::PuzzlePack, state::PutahiRule};
+
+    fn traditional(size: BoardSize) -> MoveRules {
+        MoveRules {
+            size,
+            putahi: PutahiRule::EnterAndLeave,
+            moves_played: 0,
+        }
+    }
+
+    #[test]
+    fn blocked_side_has_lost() {
+        let solution = Solution::solve(traditional(BoardSize::default()));
+        let position = StartPosition::from_text("WBBBBWW.W", PieceColor::Black);
+        assert!(moves(solution.rules, &position).is_empty());
+        assert_eq!(solution.outcome(&position), Some(Outcome::Loss(0)));
+        assert_eq!(solution.best_move(&position), None);
+    }
+
+    #[test]
+    fn blocking_move_wins_in_one() {
+        let solution = Solution::solve(traditional(BoardSize::default()));
+        let position = StartPosition::from_text(".BBBBWWWW", PieceColor::White);
+        assert_eq!(solution.outcome(&position), Some(Outcome::Win(1)));
+        assert_eq!(solution.winning_moves(&position), vec![(7, 0)]);
+        assert_eq!(solution.best_move(&position), Some((7, 0)));
+    }
+
+    #[test]
+    fn pack_puzzles_have_one_winning_move() {
+        let pack: PuzzlePack = ron::de::from_str(include_str!(
+            "../../../assets/puzzles/mu_torere.puzzles.ron"
+        ))
+        .unwrap();
+        for size in BoardSize::ALL {
+            let solution = Solution::solve(traditional(size));
+            for puzzle in pack.puzzles.iter().filter(|puzzle| puzzle.size() == size) {
+                let start = puzzle.start();
+                assert_eq!(
+                    solution.outcome(&start),
+                    Some(Outcome::Win(puzzle.moves)),
+                    "{}",
+                    puzzle.position
+                );
+                assert_eq!(
+                    solution.winning_moves(&start).len(),
+                    1,
+                    "{}",
+                    puzzle.position
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Sound effects for Mu Torere.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::{AssetGroup, LoadResource},
+    audio::{sound_effect, AudioSettings},
+    games::two_player::{GameOver, GameResult, TurnChanged, WinReason},
+};
+
+use super::{
+    animation::MoveEvent,
+    is_playing_mu_torere,
+    state::{GameState, MuTorere},
+    GAME_ID,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<PlaySound>();
+    app.load_resource_in::<MuTorereSounds>(AssetGroup::Game(GAME_ID));
+    app.add_systems(
+        Update,
+        (
+            play_move_sounds,
+            play_turn_sounds,
+            play_game_over_sounds,
+            play_requested_sounds,
+        )
+            .run_if(is_playing_mu_torere),
+    );
+}
+
+#[derive(Resource, Asset, TypePath, Clone)]
+pub struct MuTorereSounds {
+    #[dependency]
+    pickup: Handle<AudioSource>,
+    #[dependency]
+    land: Handle<AudioSource>,
+    #[dependency]
+    illegal: Handle<AudioSource>,
+    #[dependency]
+    turn: Handle<AudioSource>,
+    #[dependency]
+    win: Handle<AudioSource>,
+    #[dependency]
+    lose: Handle<AudioSource>,
+}
+
+impl FromWorld for MuTorereSounds {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            pickup: assets.load("audio/sound_effects/pickup.wav"),
+            land: assets.load("audio/sound_effects/land.wav"),
+            illegal: assets.load("audio/sound_effects/illegal.wav"),
+            turn: assets.load("audio/sound_effects/turn.wav"),
+            win: assets.load("audio/sound_effects/win.wav"),
+            lose: assets.load("audio/sound_effects/lose.wav"),
+        }
+    }
+}
+
+/// Sounds played for things that have no event of their own.
+#[derive(Message, Clone, Copy, Debug)]
+pub enum PlaySound {
+    /// A piece was dropped somewhere it can't go, or picked when it can't move.
+    Illegal,
+}
+
+fn play(commands: &mut Commands, handle: &Handle<AudioSource>, settings: &AudioSettings) {
+    commands.spawn((
+        Name::new("Sound Effect"),
+        sound_effect(handle.clone(), settings),
+    ));
+}
+
+/// A piece lifts off its node as it starts to move.
+fn play_move_sounds(
+    mut commands: Commands,
+    mut move_events: MessageReader<MoveEvent>,
+    sounds: Res<MuTorereSounds>,
+    settings: Res<AudioSettings>,
+) {
+    if move_events.read().count() > 0 {
+        play(&mut commands, &sounds.pickup, &settings);
+    }
+}
+
+/// The turn changes when a moving piece lands.
+fn play_turn_sounds(
+    mut commands: Commands,
+    mut turn_events: MessageReader<TurnChanged<MuTorere>>,
+    sounds: Res<MuTorereSounds>,
+    settings: Res<AudioSettings>,
+) {
+    if turn_events.read().count() > 0 {
+        play(&mut commands, &sounds.land, &settings);
+        play(&mut commands, &sounds.turn, &settings);
+    }
+}
+
+fn play_game_over_sounds(
+    mut commands: Commands,
+    mut game_over_events: MessageReader<GameOver<MuTorere>>,
+    sounds: Res<MuTorereSounds>,
+    audio_settings: Res<AudioSettings>,
+    game_state: Res<GameState>,
+) {
+    for event in game_over_events.read() {
+        // Running out of time or resigning ends the game without a piece landing.
+        if !matches!(
+            event.result,
+            GameResult::Win {
+                reason: WinReason::Resignation | WinReason::Timeout,
+                ..
+            }
+        ) {
+            play(&mut commands, &sounds.land, &audio_settings);
+        }
+        // Against the computer, the player can lose. Between two players,
+        // someone always wins, unless it's a draw.
+        let result = match event.result.winner() {
+            Some(winner) if game_state.is_computer(winner) => &sounds.lose,
+            Some(_) => &sounds.win,
+            None => &sounds.turn,
+        };
+        play(&mut commands, result, &audio_settings);
+    }
+}
+
+fn play_requested_sounds(
+    mut commands: Commands,
+    mut requests: MessageReader<PlaySound>,
+    sounds: Res<MuTorereSounds>,
+    settings: Res<AudioSettings>,
+) {
+    for request in requests.read() {
+        let handle = match request {
+            PlaySound::Illegal => &sounds.illegal,
+        };
+        play(&mut commands, handle, &settings);
+    }
+}
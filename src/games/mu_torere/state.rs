@@ -2,18 +2,188 @@
 
 use bevy::prelude::*;
 
+use crate::{
+    games::{
+        registry::GameId,
+        two_player::{self, Blocked, Match, SetUpMatch, TwoPlayerGame},
+    },
+    screens::{InGame, Screen},
+};
+
+use super::{
+    board::{BoardSize, MoveRules},
+    is_playing_mu_torere, GAME_ID,
+};
+
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<GameSettings>();
-    app.init_resource::<GameState>();
-    app.add_message::<TurnChangeEvent>();
-    app.add_message::<GameOverEvent>();
+    app.init_resource::<CustomStart>();
+    app.init_resource::<StartPosition>();
+    app.add_message::<RestartGame>();
+    app.add_plugins(two_player::game_plugin::<MuTorere>);
+    app.add_systems(OnEnter(Screen::Playing(GAME_ID)), choose_start_position);
+    app.add_systems(OnExit(Screen::Playing(GAME_ID)), clear_custom_start);
+    app.add_systems(
+        OnEnter(InGame),
+        set_up_match.in_set(SetUpMatch).run_if(is_playing_mu_torere),
+    );
+    app.add_systems(
+        Update,
+        (
+            choose_start_position,
+            two_player::reset_match::<MuTorere>.before(SetUpMatch),
+            set_up_match.in_set(SetUpMatch),
+        )
+            .chain()
+            .run_if(is_playing_mu_torere.and(on_message::<RestartGame>)),
+    );
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub enum GameMode {
-    #[default]
-    VsPlayer,
-    VsComputer,
+/// Starts a new match without leaving the game, from the [`CustomStart`] if
+/// one was just set. Entering the game again can't do this, as Bevy skips
+/// `OnEnter` when a state is set to itself.
+#[derive(Message)]
+pub struct RestartGame;
+
+/// Mū Tōrere's rules, as far as the [two-player framework](two_player) needs them.
+pub struct MuTorere;
+
+impl TwoPlayerGame for MuTorere {
+    type Side = PieceColor;
+    /// The nodes a piece moved from and to.
+    type Move = (usize, usize);
+    type Rules = RuleSet;
+
+    const SIDES: [PieceColor; 2] = [PieceColor::White, PieceColor::Black];
+
+    fn plays(id: GameId) -> bool {
+        id == GAME_ID
+    }
+
+    fn side_name(side: PieceColor) -> &'static str {
+        match side {
+            PieceColor::White => "White",
+            PieceColor::Black => "Black",
+        }
+    }
+
+    fn rules_label(rules: RuleSet) -> String {
+        rules.label()
+    }
+}
+
+pub type GameState = Match<MuTorere>;
+
+/// What stands on each node at the start of a game, and who moves first.
+#[derive(Resource, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct StartPosition {
+    /// Indexed like the board's nodes, so the pūtahi comes last.
+    pub nodes: Vec<Option<PieceColor>>,
+    pub to_move: PieceColor,
+}
+
+impl StartPosition {
+    /// Every kewai filled, White's half first, with White to move.
+    pub fn standard(size: BoardSize) -> Self {
+        let mut nodes: Vec<_> = (0..size.kewai)
+            .map(|kewai| Some(size.starting_color(kewai)))
+            .collect();
+        nodes.push(None);
+        Self {
+            nodes,
+            to_move: PieceColor::White,
+        }
+    }
+
+    /// Reads a position written one character per node, pūtahi last: `W` or
+    /// `B` for a piece, `.` for empty.
+    pub fn from_text(text: &str, to_move: PieceColor) -> Self {
+        Self {
+            nodes: text
+                .chars()
+                .map(|node| match node {
+                    'W' => Some(PieceColor::White),
+                    'B' => Some(PieceColor::Black),
+                    _ => None,
+                })
+                .collect(),
+            to_move,
+        }
+    }
+}
+
+/// A position set up in the start editor, for the games of this match to
+/// start from. Restarting keeps it; leaving the game clears it.
+#[derive(Resource, Default)]
+pub struct CustomStart(pub Option<StartPosition>);
+
+/// The board size and rules chosen in the options menu, set aside while a
+/// game plays under its own and put back when it ends.
+pub(super) struct SavedOptions {
+    size: BoardSize,
+    rules: RuleSet,
+}
+
+impl SavedOptions {
+    /// Sets aside the chosen options, playing on `size` under `rules` instead.
+    pub(super) fn replace(world: &mut World, size: BoardSize, rules: RuleSet) -> Self {
+        Self {
+            size: std::mem::replace(&mut *world.resource_mut::<BoardSize>(), size),
+            rules: std::mem::replace(&mut world.resource_mut::<GameSettings>().rules, rules),
+        }
+    }
+
+    pub(super) fn restore(&self, size: &mut BoardSize, settings: &mut GameSettings) {
+        *size = self.size;
+        settings.rules = self.rules;
+    }
+}
+
+/// Uses the custom start position, if one was set up for this game and board
+/// size, or else the standard one.
+pub(super) fn choose_start_position(
+    mut start: ResMut<StartPosition>,
+    custom: Res<CustomStart>,
+    size: Res<BoardSize>,
+) {
+    *start = custom
+        .0
+        .as_ref()
+        .filter(|position| position.nodes.len() == size.node_count())
+        .cloned()
+        .unwrap_or_else(|| StartPosition::standard(*size));
+}
+
+fn clear_custom_start(mut custom: ResMut<CustomStart>) {
+    custom.0 = None;
+}
+
+fn set_up_match(
+    mut game_state: ResMut<GameState>,
+    settings: Res<GameSettings>,
+    size: Res<BoardSize>,
+    start: Res<StartPosition>,
+) {
+    let rules = MoveRules {
+        size: *size,
+        putahi: settings.rules.putahi,
+        moves_played: 0,
+    };
+    // Where a blocked side passes, one that starts blocked passes straight away.
+    let first_turn = if settings.rules.blocked == BlockedRule::Passes
+        && !rules.can_move(&start.nodes, start.to_move)
+    {
+        start.to_move.opposite()
+    } else {
+        start.to_move
+    };
+    game_state.set_first_turn(first_turn);
+    game_state.set_rules(settings.rules);
+}
+
+/// For matches played to learn or practise rather than against the clock.
+pub(super) fn stop_clocks(mut game_state: ResMut<GameState>) {
+    game_state.set_untimed();
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -34,31 +204,94 @@ impl PieceColor {
 
 #[derive(Resource, Default)]
 pub struct GameSettings {
-    pub mode: GameMode,
-    pub instant_animation: bool,
+    /// The rule variants new games are played under.
+    pub rules: RuleSet,
 }
 
-#[derive(Resource, Default)]
-pub struct GameState {
-    pub current_turn: PieceColor,
-    pub game_over: bool,
-    pub winner: Option<PieceColor>,
+/// Published sources disagree on some of Mū Tōrere's rules, so these can be chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RuleSet {
+    pub putahi: PutahiRule,
+    pub blocked: BlockedRule,
 }
 
-impl GameState {
-    pub fn reset(&mut self) {
-        self.current_turn = PieceColor::White;
-        self.game_over = false;
-        self.winner = None;
+impl RuleSet {
+    /// A summary for the HUD, e.g. "Pūtahi rule: entering only, blocked player loses".
+    pub fn label(self) -> String {
+        format!(
+            "Pūtahi rule: {}, {}",
+            self.putahi.label().to_lowercase(),
+            self.blocked.label().to_lowercase()
+        )
     }
 }
 
-#[derive(Message)]
-pub struct TurnChangeEvent {
-    pub new_turn: PieceColor,
+/// When a piece must be next to an opponent's piece to move onto or off the pūtahi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PutahiRule {
+    /// Always, both entering and leaving it.
+    #[default]
+    EnterAndLeave,
+    /// Always, but only when entering it.
+    EnterOnly,
+    /// Entering and leaving it, but only for the first [`PutahiRule::OPENING_MOVES`] moves.
+    Opening,
 }
 
-#[derive(Message)]
-pub struct GameOverEvent {
-    pub winner: PieceColor,
+impl PutahiRule {
+    /// How many moves, counting both sides, [`PutahiRule::Opening`] applies for.
+    pub const OPENING_MOVES: usize = 4;
+
+    pub const ALL: [PutahiRule; 3] = [
+        PutahiRule::EnterAndLeave,
+        PutahiRule::EnterOnly,
+        PutahiRule::Opening,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PutahiRule::EnterAndLeave => "Entering and leaving",
+            PutahiRule::EnterOnly => "Entering only",
+            PutahiRule::Opening => "First four moves",
+        }
+    }
+}
+
+/// What happens to a player who has no legal move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockedRule {
+    #[default]
+    Loses,
+    /// The other player moves again. If neither can move, the game is drawn.
+    Passes,
+}
+
+impl BlockedRule {
+    pub const ALL: [BlockedRule; 2] = [BlockedRule::Loses, BlockedRule::Passes];
+
+    /// What happens once `mover` has moved, leaving `nodes`, with `rules` for
+    /// the next move.
+    pub fn after_move(
+        self,
+        rules: MoveRules,
+        nodes: &[Option<PieceColor>],
+        mover: PieceColor,
+    ) -> Blocked {
+        if rules.can_move(nodes, mover.opposite()) {
+            Blocked::No
+        } else if self == BlockedRule::Loses {
+            Blocked::OpponentLoses
+        } else if rules.can_move(nodes, mover) {
+            Blocked::OpponentPasses
+        } else {
+            Blocked::Both
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BlockedRule::Loses => "Blocked player loses",
+            BlockedRule::Passes => "Blocked player passes",
+        }
+    }
 }
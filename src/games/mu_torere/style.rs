@@ -0,0 +1,316 @@
+//! Meshes, materials and styling for drawing the Mu Torere board.
+//!
+//! The board is drawn with [`Mesh2d`] shapes so circles and rings are round and
+//! anti-aliased. Meshes and the materials that never change per entity are
+//! built once and shared; [`PieceStyle`] controls how pieces look.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+};
+
+use crate::{
+    games::two_player::board::{
+        blended, set_material_color, LINE_WIDTH, NODE_RADIUS, PIECE_RADIUS, RING_RADII,
+    },
+    theme::themes::{BoardTheme, Theme},
+};
+
+use super::{board::OUTER_RADIUS, is_playing_mu_torere, state::PieceColor};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PieceStyle>();
+    app.init_resource::<BoardAssets>();
+    app.add_systems(
+        Update,
+        (
+            apply_board_theme.run_if(resource_changed::<Theme>),
+            apply_piece_style.run_if(is_playing_mu_torere.and(resource_changed::<PieceStyle>)),
+        )
+            .chain(),
+    );
+}
+
+/// Inner and outer apothem of the square drawn around a movable piece when
+/// highlights are shape-coded. It covers about the same band as [`RING_RADII`].
+const SOURCE_FRAME_APOTHEMS: (f32, f32) = (PIECE_RADIUS + 3.0, PIECE_RADIUS + 8.0);
+/// Inner and outer apothem of the hexagon marking the last move's nodes,
+/// outside the other markers so they can show at the same time.
+const LAST_MOVE_FRAME_APOTHEMS: (f32, f32) = (PIECE_RADIUS + 11.0, PIECE_RADIUS + 15.0);
+/// Circumradius of the triangle marking a legal destination.
+const DESTINATION_MARKER_RADIUS: f32 = 12.0;
+/// Sizes of the glyphs drawn on pieces: the radius of white's dot and the
+/// circumradius of black's diamond.
+const GLYPH_DOT_RADIUS: f32 = 7.0;
+const GLYPH_DIAMOND_RADIUS: f32 = 11.0;
+/// Opacity of the faint pieces previewing where a hovered piece can move.
+const GHOST_ALPHA: f32 = 0.35;
+/// Segments used for circles; enough that the largest (the outer edge) looks smooth.
+const CIRCLE_RESOLUTION: u32 = 64;
+const OUTER_EDGE_RESOLUTION: u32 = 256;
+
+/// How pieces are drawn.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PieceStyle {
+    pub white_fill: Color,
+    pub black_fill: Color,
+    pub outline_color: Color,
+    /// Width of the outline just inside the edge of each piece. Zero for none.
+    pub outline_width: f32,
+    /// A drop shadow under each piece, or `None` for no shadow.
+    pub shadow: Option<PieceShadow>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PieceShadow {
+    pub color: Color,
+    pub offset: Vec2,
+}
+
+impl From<&BoardTheme> for PieceStyle {
+    fn from(theme: &BoardTheme) -> Self {
+        Self {
+            white_fill: theme.white_piece,
+            black_fill: theme.black_piece,
+            outline_color: theme.piece_outline,
+            outline_width: theme.piece_outline_width,
+            shadow: theme.piece_shadow.map(|shadow| PieceShadow {
+                color: shadow.color,
+                offset: shadow.offset,
+            }),
+        }
+    }
+}
+
+impl Default for PieceStyle {
+    fn default() -> Self {
+        Self::from(&Theme::default().board)
+    }
+}
+
+/// Shared meshes and materials for the board.
+#[derive(Resource, Clone)]
+pub struct BoardAssets {
+    pub node: Handle<Mesh>,
+    pub piece: Handle<Mesh>,
+    pub piece_outline: Handle<Mesh>,
+    pub ring: Handle<Mesh>,
+    pub outer_edge: Handle<Mesh>,
+    /// A line from the pūtahi to a kewai, centred on the origin and lying along +X.
+    pub spoke: Handle<Mesh>,
+    /// Shape-coded highlights: a square around a movable piece and a triangle
+    /// on a legal destination.
+    pub source_frame: Handle<Mesh>,
+    pub destination_marker: Handle<Mesh>,
+    /// A hexagon around each node of the last move.
+    pub last_move_frame: Handle<Mesh>,
+    pub white_glyph: Handle<Mesh>,
+    pub black_glyph: Handle<Mesh>,
+    pub line_material: Handle<ColorMaterial>,
+    pub white_fill: Handle<ColorMaterial>,
+    pub black_fill: Handle<ColorMaterial>,
+    /// The fills, faded, for previews of where a piece can move.
+    pub white_ghost: Handle<ColorMaterial>,
+    pub black_ghost: Handle<ColorMaterial>,
+    pub outline_material: Handle<ColorMaterial>,
+    pub shadow_material: Handle<ColorMaterial>,
+    pub marker_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for BoardAssets {
+    fn from_world(world: &mut World) -> Self {
+        let style = world.resource::<PieceStyle>().clone();
+
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let node = meshes.add(
+            Circle::new(NODE_RADIUS)
+                .mesh()
+                .resolution(CIRCLE_RESOLUTION),
+        );
+        let piece = meshes.add(
+            Circle::new(PIECE_RADIUS)
+                .mesh()
+                .resolution(CIRCLE_RESOLUTION),
+        );
+        let piece_outline = meshes.add(outline_mesh(style.outline_width));
+        let ring = meshes.add(
+            Annulus::new(RING_RADII.0, RING_RADII.1)
+                .mesh()
+                .resolution(CIRCLE_RESOLUTION),
+        );
+        let outer_edge = meshes.add(
+            Annulus::new(
+                OUTER_RADIUS - LINE_WIDTH / 2.0,
+                OUTER_RADIUS + LINE_WIDTH / 2.0,
+            )
+            .mesh()
+            .resolution(OUTER_EDGE_RESOLUTION),
+        );
+        let spoke = meshes.add(Rectangle::new(OUTER_RADIUS, LINE_WIDTH));
+        let source_frame = meshes.add(polygon_frame(4, SOURCE_FRAME_APOTHEMS));
+        let destination_marker = meshes.add(RegularPolygon::new(DESTINATION_MARKER_RADIUS, 3));
+        let last_move_frame = meshes.add(polygon_frame(6, LAST_MOVE_FRAME_APOTHEMS));
+        let white_glyph = meshes.add(
+            Circle::new(GLYPH_DOT_RADIUS)
+                .mesh()
+                .resolution(CIRCLE_RESOLUTION),
+        );
+        let black_glyph = meshes.add(RegularPolygon::new(GLYPH_DIAMOND_RADIUS, 4));
+
+        let theme = Theme::default().board;
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        Self {
+            node,
+            piece,
+            piece_outline,
+            ring,
+            outer_edge,
+            spoke,
+            source_frame,
+            destination_marker,
+            last_move_frame,
+            white_glyph,
+            black_glyph,
+            line_material: materials.add(theme.line),
+            white_fill: materials.add(blended(style.white_fill)),
+            black_fill: materials.add(blended(style.black_fill)),
+            white_ghost: materials.add(blended(style.white_fill.with_alpha(GHOST_ALPHA))),
+            black_ghost: materials.add(blended(style.black_fill.with_alpha(GHOST_ALPHA))),
+            outline_material: materials.add(blended(style.outline_color)),
+            shadow_material: materials.add(blended(
+                style.shadow.map_or(Color::NONE, |shadow| shadow.color),
+            )),
+            marker_material: materials.add(theme.highlight),
+        }
+    }
+}
+
+impl BoardAssets {
+    pub fn fill(&self, color: PieceColor) -> Handle<ColorMaterial> {
+        match color {
+            PieceColor::White => self.white_fill.clone(),
+            PieceColor::Black => self.black_fill.clone(),
+        }
+    }
+
+    pub fn ghost(&self, color: PieceColor) -> Handle<ColorMaterial> {
+        match color {
+            PieceColor::White => self.white_ghost.clone(),
+            PieceColor::Black => self.black_ghost.clone(),
+        }
+    }
+
+    pub fn glyph(&self, color: PieceColor) -> Handle<Mesh> {
+        match color {
+            PieceColor::White => self.white_glyph.clone(),
+            PieceColor::Black => self.black_glyph.clone(),
+        }
+    }
+}
+
+fn outline_mesh(width: f32) -> Mesh {
+    let width = width.clamp(0.0, PIECE_RADIUS);
+    Annulus::new(PIECE_RADIUS - width, PIECE_RADIUS)
+        .mesh()
+        .resolution(CIRCLE_RESOLUTION)
+        .into()
+}
+
+/// The outline of a regular polygon with a flat side at the bottom, between
+/// two apothems (distances from the centre to the middle of a side).
+fn polygon_frame(sides: u32, (inner, outer): (f32, f32)) -> Mesh {
+    let step = TAU / sides as f32;
+    let first_corner = -FRAC_PI_2 - step / 2.0;
+    // Corners are further out than the middle of the sides.
+    let corner_scale = 1.0 / (step / 2.0).cos();
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for i in 0..sides {
+        let corner = Vec2::from_angle(first_corner + i as f32 * step) * corner_scale;
+        positions.push((corner * inner).extend(0.0).to_array());
+        positions.push((corner * outer).extend(0.0).to_array());
+        let (inner_a, outer_a) = (2 * i, 2 * i + 1);
+        let (inner_b, outer_b) = ((2 * i + 2) % (2 * sides), (2 * i + 3) % (2 * sides));
+        indices.extend([inner_a, outer_a, outer_b, inner_a, outer_b, inner_b]);
+    }
+    let vertex_count = positions.len();
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; vertex_count])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; vertex_count])
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Restyles the pieces and recolours the lines for a new theme.
+/// Nodes and rings pick up their theme colours as they update.
+fn apply_board_theme(
+    theme: Res<Theme>,
+    board_assets: Res<BoardAssets>,
+    mut style: ResMut<PieceStyle>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    style.set_if_neq(PieceStyle::from(&theme.board));
+    set_material_color(
+        &mut materials,
+        &board_assets.line_material,
+        theme.board.line,
+    );
+    set_material_color(
+        &mut materials,
+        &board_assets.marker_material,
+        theme.board.highlight,
+    );
+}
+
+/// The drop shadow behind a piece.
+#[derive(Component)]
+pub struct PieceShadowVisual;
+
+fn apply_piece_style(
+    style: Res<PieceStyle>,
+    board_assets: Res<BoardAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut shadows: Query<(&mut Transform, &mut Visibility), With<PieceShadowVisual>>,
+) {
+    for (handle, color) in [
+        (&board_assets.white_fill, style.white_fill),
+        (&board_assets.black_fill, style.black_fill),
+        (
+            &board_assets.white_ghost,
+            style.white_fill.with_alpha(GHOST_ALPHA),
+        ),
+        (
+            &board_assets.black_ghost,
+            style.black_fill.with_alpha(GHOST_ALPHA),
+        ),
+        (&board_assets.outline_material, style.outline_color),
+        (
+            &board_assets.shadow_material,
+            style.shadow.map_or(Color::NONE, |shadow| shadow.color),
+        ),
+    ] {
+        set_material_color(&mut materials, handle, color);
+    }
+    let _ = meshes.insert(
+        &board_assets.piece_outline,
+        outline_mesh(style.outline_width),
+    );
+
+    for (mut transform, mut visibility) in &mut shadows {
+        match style.shadow {
+            Some(shadow) => {
+                transform.translation = shadow.offset.extend(transform.translation.z);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! A tutorial that teaches the rules one move at a time.
+//!
+//! Each step sets up a position on the traditional board, explains a rule and
+//! allows only the move that shows it. Once that move is played, the step says
+//! what it showed and the next step can be started.
+
+use bevy::prelude::*;
+
+use crate::{
+    games::two_player::{GameMode, SetUpMatch},
+    menus::start_game,
+    screens::{gameplay::HUD_HEIGHT, InGame, Screen},
+    theme::{accessibility::Announce, widget},
+};
+
+use super::{
+    board::BoardSize,
+    input::AllowedMoves,
+    is_playing_mu_torere,
+    state::{
+        stop_clocks, CustomStart, GameSettings, GameState, PieceColor, RestartGame, RuleSet,
+        SavedOptions, StartPosition,
+    },
+    GAME_ID,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::MainMenu),
+        end_tutorial.run_if(resource_exists::<Tutorial>),
+    );
+    app.add_systems(
+        OnEnter(Screen::Playing(GAME_ID)),
+        spawn_tutorial_panel.run_if(resource_exists::<Tutorial>),
+    );
+    app.add_systems(
+        OnEnter(InGame),
+        stop_clocks
+            .in_set(SetUpMatch)
+            .run_if(is_playing_mu_torere.and(resource_exists::<Tutorial>)),
+    );
+    app.add_systems(
+        Update,
+        (
+            stop_clocks
+                .in_set(SetUpMatch)
+                .run_if(on_message::<RestartGame>),
+            (limit_moves, update_tutorial_panel),
+        )
+            .run_if(is_playing_mu_torere.and(resource_exists::<Tutorial>)),
+    );
+}
+
+/// One lesson: a position, what to do in it, and what doing it showed.
+struct Step {
+    title: &'static str,
+    instruction: &'static str,
+    success: &'static str,
+    /// Written as in [`StartPosition::from_text`], on the 8-kewai board. White moves first.
+    position: &'static str,
+    /// The move the step teaches, the only one it allows.
+    lesson: (usize, usize),
+}
+
+impl Step {
+    fn start(&self) -> StartPosition {
+        StartPosition::from_text(self.position, PieceColor::White)
+    }
+}
+
+/// Kewai are numbered from 1 in the text, one more than their node index.
+const STEPS: [Step; 4] = [
+    Step {
+        title: "Moving along the ring",
+        instruction: "A piece moves one step along a line, onto an empty point. \
+            Move White's piece on kewai 3 to the empty kewai 4 next to it.",
+        success: "Well done. Every move is a single step onto an empty point.",
+        position: "WWW.BBBWB",
+        lesson: (2, 3),
+    },
+    Step {
+        title: "Entering the pūtahi",
+        instruction: "A piece may only move onto the pūtahi, the centre, when it is next \
+            to one of the other side's pieces. The pieces on kewai 2 and 3 only touch \
+            White pieces, so they can't. Move the piece on kewai 4 onto the pūtahi.",
+        success: "Well done. Kewai 4 is next to Black's piece on kewai 5, so it could move in.",
+        position: "WWWWBBBB.",
+        lesson: (3, 8),
+    },
+    Step {
+        title: "Leaving the pūtahi",
+        instruction: "The same rule applies when leaving the pūtahi, but the pūtahi is \
+            next to every kewai, so a piece there can always move out to an empty one. \
+            Move White's piece from the pūtahi to kewai 6.",
+        success: "Well done. Pieces move in and out of the pūtahi as the game goes on.",
+        position: "WWWBB.BBW",
+        lesson: (8, 5),
+    },
+    Step {
+        title: "Winning by blocking",
+        instruction: "You win when the other side has no move on their turn. \
+            Move the piece on kewai 8 to kewai 1 and see whether Black can still move.",
+        success: "Black can't move, so White wins. That's all the rules: try a game!",
+        position: ".BBBBWWWW",
+        lesson: (7, 0),
+    },
+];
+
+/// The step being played, while the tutorial is running.
+#[derive(Resource)]
+struct Tutorial {
+    step: usize,
+    /// The step and whether it was done, as the panel last showed them.
+    shown: Option<(usize, bool)>,
+    options: SavedOptions,
+}
+
+/// Starts the first step, on the traditional board and rules until the
+/// tutorial ends.
+pub(super) fn start_tutorial(world: &mut World) {
+    let options = SavedOptions::replace(world, BoardSize::default(), RuleSet::default());
+    world.resource_mut::<CustomStart>().0 = Some(STEPS[0].start());
+    world.insert_resource(Tutorial {
+        step: 0,
+        shown: None,
+        options,
+    });
+    start_game(world, GAME_ID, |world| {
+        world.insert_resource(GameMode::VsPlayer);
+    });
+}
+
+/// Puts back the options the tutorial played without.
+fn end_tutorial(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    mut size: ResMut<BoardSize>,
+    mut settings: ResMut<GameSettings>,
+) {
+    tutorial.options.restore(&mut size, &mut settings);
+    commands.remove_resource::<Tutorial>();
+}
+
+/// Whether the step's move has been played.
+fn is_step_done(game_state: &GameState) -> bool {
+    game_state.moves_played() > 0 && !game_state.is_moving()
+}
+
+/// Allows only the step's move, and no more moves once it is played.
+fn limit_moves(
+    tutorial: Res<Tutorial>,
+    game_state: Res<GameState>,
+    mut allowed: ResMut<AllowedMoves>,
+) {
+    let moves = if game_state.moves_played() > 0 {
+        Vec::new()
+    } else {
+        vec![STEPS[tutorial.step].lesson]
+    };
+    allowed.set_if_neq(AllowedMoves(Some(moves)));
+}
+
+#[derive(Component)]
+struct StepTitle;
+
+#[derive(Component)]
+struct StepText;
+
+/// Holds the button to the next step once the step is done.
+#[derive(Component)]
+struct NextStep;
+
+/// Beside the board, where it leaves the most room on a wide window.
+fn spawn_tutorial_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Tutorial Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(HUD_HEIGHT),
+            left: Val::Px(20.0),
+            width: Val::Px(320.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Start,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        // Above the results screen, so the last step can be finished from it.
+        GlobalZIndex(4),
+        Pickable::IGNORE,
+        StateScoped(Screen::Playing(GAME_ID)),
+        children![
+            (widget::label(""), StepTitle),
+            (widget::label(""), StepText),
+            (Name::new("Next Step"), NextStep, Node::default()),
+        ],
+    ));
+}
+
+fn update_tutorial_panel(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    game_state: Res<GameState>,
+    mut title: Single<&mut Text, (With<StepTitle>, Without<StepText>)>,
+    mut text: Single<&mut Text, (With<StepText>, Without<StepTitle>)>,
+    next: Single<Entity, With<NextStep>>,
+    mut announce: MessageWriter<Announce>,
+) {
+    let done = is_step_done(&game_state);
+    if tutorial.shown == Some((tutorial.step, done)) {
+        return;
+    }
+    tutorial.shown = Some((tutorial.step, done));
+
+    let step = &STEPS[tutorial.step];
+    title.0 = format!(
+        "Step {} of {}: {}",
+        tutorial.step + 1,
+        STEPS.len(),
+        step.title
+    );
+    let message = if done { step.success } else { step.instruction };
+    text.0 = message.to_string();
+    announce.write(Announce(message.to_string()));
+
+    let mut next = commands.entity(*next);
+    next.despawn_related::<Children>();
+    if done {
+        let label = if tutorial.step + 1 == STEPS.len() {
+            "Finish"
+        } else {
+            "Next"
+        };
+        next.with_child(widget::button_medium(label, next_step));
+    }
+}
+
+fn next_step(
+    _: On<Pointer<Click>>,
+    mut tutorial: ResMut<Tutorial>,
+    mut custom: ResMut<CustomStart>,
+    mut restart: MessageWriter<RestartGame>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if tutorial.step + 1 == STEPS.len() {
+        next_screen.set(Screen::MainMenu);
+        return;
+    }
+    tutorial.step += 1;
+    custom.0 = Some(STEPS[tutorial.step].start());
+    restart.write(RestartGame);
+}
@@ -0,0 +1,97 @@
+//! The list of games, which the menus, loading screen and gameplay screen are
+//! built from.
+//!
+//! Each game's plugin registers itself with [`RegisterGame::register_game`]
+//! (or, for games defined by asset files, with [`GameRegistry::register`] once
+//! they load),
+//! loads its assets with [`load_resource_in`](crate::asset_tracking::LoadResource::load_resource_in)
+//! under [`AssetGroup::Game`](crate::asset_tracking::AssetGroup::Game), and
+//! gates its systems on `Screen::Playing` with its id.
+
+use bevy::prelude::*;
+
+use crate::menus::Menu;
+
+/// Identifies a game, e.g. in [`Screen`](crate::screens::Screen) states and asset groups.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct GameId(pub &'static str);
+
+/// What the menus need to know about a game.
+#[derive(Clone)]
+pub struct Game {
+    pub id: GameId,
+    /// The name shown in the menus.
+    pub name: &'static str,
+    /// The ways to start the game, shown as buttons on its options menu.
+    pub options: Vec<GameOption>,
+    /// Choices shown above the options, which apply to whichever way the game is started.
+    pub settings: Vec<GameSetting>,
+}
+
+/// A button on a game's options menu, e.g. "vs Computer".
+#[derive(Clone, Copy)]
+pub struct GameOption {
+    pub label: &'static str,
+    pub action: OptionAction,
+}
+
+/// What choosing a [`GameOption`] does.
+#[derive(Clone, Copy)]
+pub enum OptionAction {
+    /// Applies the option, then starts the game.
+    Start(fn(&mut World)),
+    /// Opens a menu, e.g. an editor, which starts the game itself.
+    Open(Menu),
+}
+
+/// A choice on a game's options menu, e.g. the board size, shown as its
+/// current value between buttons that step it back and forth.
+#[derive(Clone, Copy)]
+pub struct GameSetting {
+    pub label: &'static str,
+    /// The current value, as shown on the menu.
+    pub value: fn(&World) -> String,
+    /// Steps the value by the given number of choices, wrapping around.
+    pub step: fn(&mut World, isize),
+}
+
+/// Every registered game, in the order the game select menu lists them.
+#[derive(Resource, Default)]
+pub struct GameRegistry {
+    games: Vec<Game>,
+}
+
+impl GameRegistry {
+    pub fn iter(&self) -> impl Iterator<Item = &Game> {
+        self.games.iter()
+    }
+
+    pub fn get(&self, id: GameId) -> Option<&Game> {
+        self.games.iter().find(|game| game.id == id)
+    }
+
+    /// Adds a game to the menus, for games only known once the app is running.
+    /// Others use [`RegisterGame::register_game`].
+    pub fn register(&mut self, game: Game) {
+        assert!(
+            self.get(game.id).is_none(),
+            "game {:?} registered twice",
+            game.id
+        );
+        self.games.push(game);
+    }
+}
+
+pub trait RegisterGame {
+    /// Adds a game to the menus.
+    fn register_game(&mut self, game: Game) -> &mut Self;
+}
+
+impl RegisterGame for App {
+    fn register_game(&mut self, game: Game) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameRegistry>()
+            .register(game);
+        self
+    }
+}
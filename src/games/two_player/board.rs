@@ -0,0 +1,35 @@
+//! Sizes and materials shared by the boards of two-player games, so pieces and
+//! nodes look the same in every game.
+
+use bevy::{prelude::*, sprite_render::AlphaMode2d};
+
+pub const PIECE_RADIUS: f32 = 25.0;
+/// Radius of the circle drawn for an empty board node.
+pub const NODE_RADIUS: f32 = PIECE_RADIUS + 5.0;
+/// Inner and outer radius of the highlight and focus rings around a piece.
+pub const RING_RADII: (f32, f32) = (PIECE_RADIUS + 2.0, PIECE_RADIUS + 8.0);
+/// Width of the lines pieces move along.
+pub const LINE_WIDTH: f32 = 4.0;
+
+/// A material whose colour can be changed to or from translucent later.
+pub fn blended(color: Color) -> ColorMaterial {
+    ColorMaterial {
+        color,
+        alpha_mode: AlphaMode2d::Blend,
+        ..default()
+    }
+}
+
+/// Sets the colour of a material, if it isn't that colour already.
+/// (Touching a material re-uploads it, so this avoids doing so every frame.)
+pub fn set_material_color(
+    materials: &mut Assets<ColorMaterial>,
+    handle: &Handle<ColorMaterial>,
+    color: Color,
+) {
+    if materials.get(handle).is_some_and(|m| m.color != color)
+        && let Some(material) = materials.get_mut(handle)
+    {
+        material.color = color;
+    }
+}
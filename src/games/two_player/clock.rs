@@ -0,0 +1,139 @@
+//! Game clocks. Each side has a fixed amount of time for the whole game, which
+//! only runs on its own turn. Running out loses the game.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems};
+
+use super::{is_playing_game, side_index, GameOver, GameResult, Match, TwoPlayerGame, WinReason};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TimeControl>();
+}
+
+pub(super) fn game_plugin<G: TwoPlayerGame>(app: &mut App) {
+    app.add_systems(
+        Update,
+        tick_clock::<G>
+            .run_if(is_playing_game::<G>)
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+}
+
+/// How long each side gets for a game. Takes effect from the next game.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeControl {
+    #[default]
+    Untimed,
+    Minutes(u32),
+}
+
+impl TimeControl {
+    /// Every choice, in the order the settings menu steps through them.
+    pub const ALL: [TimeControl; 5] = [
+        TimeControl::Untimed,
+        TimeControl::Minutes(1),
+        TimeControl::Minutes(3),
+        TimeControl::Minutes(5),
+        TimeControl::Minutes(10),
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            TimeControl::Untimed => "Off".to_string(),
+            TimeControl::Minutes(1) => "1 minute".to_string(),
+            TimeControl::Minutes(minutes) => format!("{minutes} minutes"),
+        }
+    }
+
+    /// Each side's time, if the game is timed.
+    pub fn seconds(self) -> Option<f32> {
+        match self {
+            TimeControl::Untimed => None,
+            TimeControl::Minutes(minutes) => Some(minutes as f32 * 60.0),
+        }
+    }
+}
+
+fn tick_clock<G: TwoPlayerGame>(
+    time: Res<Time>,
+    mut state: ResMut<Match<G>>,
+    mut game_over: MessageWriter<GameOver<G>>,
+) {
+    if state.is_over() {
+        return;
+    }
+    let side = state.turn;
+    let Some(clocks) = &mut state.clocks else {
+        return;
+    };
+    let left = &mut clocks[side_index::<G>(side)];
+    *left = (*left - time.delta_secs()).max(0.0);
+    if *left <= 0.0 {
+        let result = GameResult::Win {
+            winner: G::opponent(side),
+            reason: WinReason::Timeout,
+        };
+        state.result = Some(result);
+        game_over.write(GameOver { result });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::games::two_player::{
+        tests::{new_match, TestGame},
+        GameMode,
+    };
+
+    fn tick(app: &mut App, seconds: u64) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(seconds));
+        app.world_mut()
+            .run_system_cached(tick_clock::<TestGame>)
+            .unwrap();
+    }
+
+    #[test]
+    fn only_the_side_to_move_uses_time() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Minutes(1));
+        app.init_resource::<Time>();
+        tick(&mut app, 10);
+        let state = app.world().resource::<Match<TestGame>>();
+        assert_eq!(state.time_left(0), Some(50.0));
+        assert_eq!(state.time_left(1), Some(60.0));
+    }
+
+    #[test]
+    fn running_out_of_time_loses() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Minutes(1));
+        app.init_resource::<Time>();
+        tick(&mut app, 61);
+        let state = app.world().resource::<Match<TestGame>>();
+        assert_eq!(state.time_left(0), Some(0.0));
+        assert_eq!(
+            state.result(),
+            Some(GameResult::Win {
+                winner: 1,
+                reason: WinReason::Timeout,
+            })
+        );
+        let game_overs = app.world().resource::<Messages<GameOver<TestGame>>>();
+        assert_eq!(game_overs.len(), 1);
+    }
+
+    #[test]
+    fn untimed_games_have_no_clock() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Untimed);
+        app.init_resource::<Time>();
+        tick(&mut app, 600);
+        let state = app.world().resource::<Match<TestGame>>();
+        assert_eq!(state.time_left(0), None);
+        assert!(!state.is_over());
+    }
+}
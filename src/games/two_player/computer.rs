@@ -0,0 +1,69 @@
+//! The hook for a computer player. The framework decides when the computer
+//! moves; the game decides what it plays.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems};
+
+use super::{is_playing_game, Match, TwoPlayerGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ComputerThinkTimer>();
+}
+
+pub(super) fn game_plugin<G: TwoPlayerGame>(app: &mut App) {
+    app.add_message::<ComputerTurn<G>>();
+    app.add_systems(
+        Update,
+        think::<G>
+            .run_if(is_playing_game::<G>)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// It's the computer's turn to move as `side`. The game answers by starting a move.
+#[derive(Message)]
+pub struct ComputerTurn<G: TwoPlayerGame> {
+    pub side: G::Side,
+}
+
+/// A short pause before the computer moves, so its moves are easy to follow.
+#[derive(Resource)]
+struct ComputerThinkTimer {
+    timer: Timer,
+    thinking: bool,
+}
+
+impl Default for ComputerThinkTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.5, TimerMode::Once),
+            thinking: false,
+        }
+    }
+}
+
+fn think<G: TwoPlayerGame>(
+    time: Res<Time>,
+    mut think_timer: ResMut<ComputerThinkTimer>,
+    state: Res<Match<G>>,
+    mut turns: MessageWriter<ComputerTurn<G>>,
+) {
+    if state.is_over() || state.is_moving() || state.is_human_turn() {
+        think_timer.thinking = false;
+        return;
+    }
+
+    if !think_timer.thinking {
+        think_timer.thinking = true;
+        think_timer.timer.reset();
+        return;
+    }
+
+    think_timer.timer.tick(time.delta());
+    if think_timer.timer.is_finished() {
+        turns.write(ComputerTurn { side: state.turn });
+        think_timer.thinking = false;
+    }
+}
@@ -0,0 +1,714 @@
+//! Shared rules and UI for two-player, turn-based board games.
+//!
+//! A game implements [`TwoPlayerGame`] and adds [`game_plugin::<G>`](game_plugin).
+//! In return it gets turn tracking, a move history with undo and redo, clocks,
+//! resignation, a hook for the computer player, and the HUD and results screen.
+//!
+//! The game still owns its board. It tells the framework when a move has
+//! finished with [`MoveCompleted`], and moves its pieces for the moves the
+//! framework takes back and replays with [`MoveUndone`] and [`MoveRedone`].
+
+pub mod board;
+mod clock;
+mod computer;
+mod ui;
+
+use std::{fmt::Debug, hash::Hash};
+
+use bevy::prelude::*;
+
+pub use clock::TimeControl;
+pub use computer::ComputerTurn;
+
+use crate::{
+    games::registry::{GameId, GameOption, OptionAction},
+    input_map::{action_just_pressed, InputAction},
+    menus::Menu,
+    screens::{is_playing, InGame, Screen},
+    AppSystems, PausableSystems,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameMode>();
+    app.add_message::<MatchCommand>();
+    app.add_plugins((clock::plugin, computer::plugin, ui::plugin));
+    app.configure_sets(OnEnter(InGame), SetUpMatch);
+
+    app.add_systems(
+        Update,
+        (
+            write_match_command(MatchCommand::Undo).run_if(action_just_pressed(InputAction::Undo)),
+            write_match_command(MatchCommand::Redo).run_if(action_just_pressed(InputAction::Redo)),
+        )
+            .run_if(is_playing.and(in_state(Menu::None)))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Adds the framework's systems for `G`.
+pub fn game_plugin<G: TwoPlayerGame>(app: &mut App) {
+    app.init_resource::<Match<G>>();
+    app.add_message::<MoveCompleted<G>>();
+    app.add_message::<MoveUndone<G>>();
+    app.add_message::<MoveRedone<G>>();
+    app.add_message::<TurnChanged<G>>();
+    app.add_message::<GameOver<G>>();
+
+    app.add_systems(
+        OnEnter(InGame),
+        reset_match::<G>
+            .before(SetUpMatch)
+            .run_if(is_playing_game::<G>),
+    );
+    app.add_systems(
+        Update,
+        (complete_moves::<G>, handle_match_commands::<G>)
+            .chain()
+            .run_if(is_playing_game::<G>)
+            .in_set(AppSystems::Update),
+    );
+    app.add_plugins((
+        clock::game_plugin::<G>,
+        computer::game_plugin::<G>,
+        ui::game_plugin::<G>,
+    ));
+}
+
+/// A two-player game the framework can run.
+pub trait TwoPlayerGame: Send + Sync + 'static {
+    /// One of the two players, e.g. a piece colour.
+    type Side: Copy + Eq + Hash + Debug + Send + Sync + 'static;
+    /// A move, with enough detail to take it back.
+    type Move: Copy + Debug + Send + Sync + 'static;
+    /// The rule variants a match can be played under, for games that have them.
+    type Rules: Copy + Debug + Send + Sync + 'static;
+
+    /// Both sides, in the order they take turns.
+    const SIDES: [Self::Side; 2];
+
+    /// Returns true if this game plays the registered game `id`. One game can
+    /// play several, e.g. a runtime for games defined in data files.
+    fn plays(id: GameId) -> bool;
+
+    /// The side's name, e.g. "White".
+    fn side_name(side: Self::Side) -> &'static str;
+
+    /// A summary of `rules` for the HUD and results screen.
+    fn rules_label(rules: Self::Rules) -> String;
+
+    /// The side that moves after `side`.
+    fn opponent(side: Self::Side) -> Self::Side {
+        if side == Self::SIDES[0] {
+            Self::SIDES[1]
+        } else {
+            Self::SIDES[0]
+        }
+    }
+}
+
+/// Whether the second side is played by another person or the computer.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameMode {
+    #[default]
+    VsPlayer,
+    VsComputer,
+}
+
+/// The "vs Player" and "vs Computer" options for a game's options menu.
+pub fn mode_options() -> Vec<GameOption> {
+    vec![
+        GameOption {
+            label: "vs Player",
+            action: OptionAction::Start(|world| world.insert_resource(GameMode::VsPlayer)),
+        },
+        GameOption {
+            label: "vs Computer",
+            action: OptionAction::Start(|world| world.insert_resource(GameMode::VsComputer)),
+        },
+    ]
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult<S> {
+    Win { winner: S, reason: WinReason },
+    Draw(DrawReason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinReason {
+    /// The loser has no legal move.
+    OpponentBlocked,
+    /// The winner met the game's own goal, e.g. reached certain nodes.
+    GoalReached,
+    Resignation,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    /// Neither side has a legal move, in a game where a blocked side passes.
+    NoMoves,
+}
+
+impl<S: Copy> GameResult<S> {
+    pub fn winner(self) -> Option<S> {
+        match self {
+            GameResult::Win { winner, .. } => Some(winner),
+            GameResult::Draw(_) => None,
+        }
+    }
+
+    /// A headline for the result, e.g. "White Wins!".
+    pub fn headline<G: TwoPlayerGame<Side = S>>(self) -> String {
+        match self {
+            GameResult::Win { winner, .. } => format!("{} Wins!", G::side_name(winner)),
+            GameResult::Draw(_) => "Draw".to_string(),
+        }
+    }
+
+    /// Why the game ended, e.g. "Black has no moves."
+    pub fn reason<G: TwoPlayerGame<Side = S>>(self) -> String {
+        match self {
+            GameResult::Win { winner, reason } => {
+                let loser = G::side_name(G::opponent(winner));
+                match reason {
+                    WinReason::OpponentBlocked => format!("{loser} has no moves."),
+                    WinReason::GoalReached => {
+                        format!("{} reached the goal.", G::side_name(winner))
+                    }
+                    WinReason::Resignation => format!("{loser} resigned."),
+                    WinReason::Timeout => format!("{loser} ran out of time."),
+                }
+            }
+            GameResult::Draw(DrawReason::NoMoves) => "Neither side can move.".to_string(),
+        }
+    }
+}
+
+/// A move in the history, with what it led to.
+struct PlayedMove<G: TwoPlayerGame> {
+    side: G::Side,
+    mv: G::Move,
+    /// The other side had no move and passed, so the mover moves again.
+    opponent_passed: bool,
+    /// The result, if the move ended the game.
+    result: Option<GameResult<G::Side>>,
+}
+
+impl<G: TwoPlayerGame> Clone for PlayedMove<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: TwoPlayerGame> Copy for PlayedMove<G> {}
+
+/// The game being played: whose turn it is, how it ended, and the moves so far.
+#[derive(Resource)]
+pub struct Match<G: TwoPlayerGame> {
+    turn: G::Side,
+    result: Option<GameResult<G::Side>>,
+    history: Vec<PlayedMove<G>>,
+    /// Moves that were undone and can be redone, the most recently undone last.
+    undone: Vec<PlayedMove<G>>,
+    /// The side the computer plays, if any.
+    computer: Option<G::Side>,
+    /// Set while a move is under way, e.g. while its piece animates.
+    moving: bool,
+    /// Time left for each side, in [`TwoPlayerGame::SIDES`] order, if the game is timed.
+    clocks: Option<[f32; 2]>,
+    /// The rule variants the game is played under, for games that have them.
+    rules: Option<G::Rules>,
+}
+
+impl<G: TwoPlayerGame> Default for Match<G> {
+    fn default() -> Self {
+        Self {
+            turn: G::SIDES[0],
+            result: None,
+            history: Vec::new(),
+            undone: Vec::new(),
+            computer: None,
+            moving: false,
+            clocks: None,
+            rules: None,
+        }
+    }
+}
+
+impl<G: TwoPlayerGame> Match<G> {
+    /// The side to move.
+    pub fn turn(&self) -> G::Side {
+        self.turn
+    }
+
+    pub fn result(&self) -> Option<GameResult<G::Side>> {
+        self.result
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// How many moves have been played, not counting undone ones.
+    pub fn moves_played(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn last_move(&self) -> Option<G::Move> {
+        self.history.last().map(|played| played.mv)
+    }
+
+    /// The side that played [`last_move`](Self::last_move).
+    pub fn last_mover(&self) -> Option<G::Side> {
+        self.history.last().map(|played| played.side)
+    }
+
+    /// Returns true if `side` is played by the computer.
+    pub fn is_computer(&self, side: G::Side) -> bool {
+        self.computer == Some(side)
+    }
+
+    /// Returns true if the side to move is controlled by a person at this computer.
+    pub fn is_human_turn(&self) -> bool {
+        !self.is_computer(self.turn)
+    }
+
+    /// Marks a move as under way. The framework clears it when the move completes.
+    pub fn start_move(&mut self) {
+        self.moving = true;
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.moving
+    }
+
+    /// Timed games can't be taken back, or the clocks would mean nothing.
+    pub fn can_undo(&self) -> bool {
+        !self.moving && self.clocks.is_none() && !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.moving && self.clocks.is_none() && !self.undone.is_empty()
+    }
+
+    /// Starts the game with `side` to move, e.g. from a custom position.
+    /// Only has an effect before the first move.
+    pub fn set_first_turn(&mut self, side: G::Side) {
+        if self.history.is_empty() {
+            self.turn = side;
+        }
+    }
+
+    /// Turns the clocks off, e.g. for a puzzle. Only has an effect before the first move.
+    pub fn set_untimed(&mut self) {
+        if self.history.is_empty() {
+            self.clocks = None;
+        }
+    }
+
+    /// The rule variants the game is played under, if the game recorded them.
+    pub fn rules(&self) -> Option<G::Rules> {
+        self.rules
+    }
+
+    /// Records the rule variants for this game, once it has been reset.
+    pub fn set_rules(&mut self, rules: G::Rules) {
+        self.rules = Some(rules);
+    }
+
+    /// Seconds left on `side`'s clock, if the game is timed.
+    pub fn time_left(&self, side: G::Side) -> Option<f32> {
+        self.clocks.map(|clocks| clocks[side_index::<G>(side)])
+    }
+
+    /// The person playing. In a game between two people, the side to move.
+    fn human_side(&self) -> G::Side {
+        match self.computer {
+            Some(computer) => G::opponent(computer),
+            None => self.turn,
+        }
+    }
+
+    /// Moves the turn on past `played`, or ends the game if it did.
+    fn advance(&mut self, played: PlayedMove<G>) {
+        self.result = played.result;
+        if played.result.is_none() && !played.opponent_passed {
+            self.turn = G::opponent(played.side);
+        }
+        self.history.push(played);
+    }
+}
+
+/// A run condition that is true while one of `G`'s games is being played.
+pub fn is_playing_game<G: TwoPlayerGame>(screen: Res<State<Screen>>) -> bool {
+    matches!(screen.get(), Screen::Playing(id) if G::plays(*id))
+}
+
+fn side_index<G: TwoPlayerGame>(side: G::Side) -> usize {
+    usize::from(side != G::SIDES[0])
+}
+
+/// Written by the game when a move has finished, e.g. once its piece lands.
+#[derive(Message)]
+pub struct MoveCompleted<G: TwoPlayerGame> {
+    pub mv: G::Move,
+    /// Whether the side to move next has a legal move, and what happens if not.
+    pub blocked: Blocked,
+    /// The mover met the game's own win condition.
+    pub goal_reached: bool,
+}
+
+/// What happens after a move to a side without a legal move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Blocked {
+    /// The side to move next can move.
+    #[default]
+    No,
+    /// The side to move next has no legal move, and loses.
+    OpponentLoses,
+    /// The side to move next has no legal move, and passes.
+    OpponentPasses,
+    /// Neither side has a legal move, in a game where blocked sides pass.
+    Both,
+}
+
+/// A move was taken back. The game should put its piece back at once.
+#[derive(Message)]
+pub struct MoveUndone<G: TwoPlayerGame> {
+    pub side: G::Side,
+    pub mv: G::Move,
+}
+
+/// An undone move was played again. The game should make it at once.
+#[derive(Message)]
+pub struct MoveRedone<G: TwoPlayerGame> {
+    pub side: G::Side,
+    pub mv: G::Move,
+}
+
+/// A move finished and the other side is to move.
+#[derive(Message)]
+pub struct TurnChanged<G: TwoPlayerGame> {
+    pub side: G::Side,
+}
+
+#[derive(Message)]
+pub struct GameOver<G: TwoPlayerGame> {
+    pub result: GameResult<G::Side>,
+}
+
+/// A request from the player about the game as a whole, rather than its board.
+#[derive(Message, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchCommand {
+    Undo,
+    Redo,
+    Resign,
+}
+
+fn write_match_command(command: MatchCommand) -> impl Fn(MessageWriter<MatchCommand>) {
+    move |mut commands| {
+        commands.write(command);
+    }
+}
+
+/// Where games record something about a new match, like its rules, on entering
+/// the game: after [`reset_match`] and before the HUD is spawned.
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SetUpMatch;
+
+/// Starts a new match. Games that record something about it, like its rules,
+/// do so in [`SetUpMatch`].
+pub fn reset_match<G: TwoPlayerGame>(
+    mut state: ResMut<Match<G>>,
+    mode: Res<GameMode>,
+    time_control: Res<TimeControl>,
+) {
+    *state = Match {
+        computer: (*mode == GameMode::VsComputer).then_some(G::SIDES[1]),
+        clocks: time_control.seconds().map(|seconds| [seconds; 2]),
+        ..default()
+    };
+}
+
+fn complete_moves<G: TwoPlayerGame>(
+    mut completed: MessageReader<MoveCompleted<G>>,
+    mut state: ResMut<Match<G>>,
+    mut turns: MessageWriter<TurnChanged<G>>,
+    mut game_over: MessageWriter<GameOver<G>>,
+) {
+    for event in completed.read() {
+        state.moving = false;
+        // The game can end mid-move, e.g. if the mover's clock runs out.
+        if state.is_over() {
+            continue;
+        }
+        let side = state.turn;
+        let result = if event.goal_reached {
+            Some(GameResult::Win {
+                winner: side,
+                reason: WinReason::GoalReached,
+            })
+        } else if event.blocked == Blocked::OpponentLoses {
+            Some(GameResult::Win {
+                winner: side,
+                reason: WinReason::OpponentBlocked,
+            })
+        } else if event.blocked == Blocked::Both {
+            Some(GameResult::Draw(DrawReason::NoMoves))
+        } else {
+            None
+        };
+        state.undone.clear();
+        state.advance(PlayedMove {
+            side,
+            mv: event.mv,
+            opponent_passed: event.blocked == Blocked::OpponentPasses,
+            result,
+        });
+        match result {
+            Some(result) => {
+                game_over.write(GameOver { result });
+            }
+            None => {
+                turns.write(TurnChanged { side: state.turn });
+            }
+        }
+    }
+}
+
+fn handle_match_commands<G: TwoPlayerGame>(
+    mut commands: MessageReader<MatchCommand>,
+    mut state: ResMut<Match<G>>,
+    mut undone: MessageWriter<MoveUndone<G>>,
+    mut redone: MessageWriter<MoveRedone<G>>,
+    mut game_over: MessageWriter<GameOver<G>>,
+) {
+    for command in commands.read() {
+        match command {
+            MatchCommand::Undo => {
+                // Against the computer, take back its reply too.
+                while state.can_undo() {
+                    let Some(played) = state.history.pop() else {
+                        break;
+                    };
+                    state.turn = played.side;
+                    state.result = None;
+                    state.undone.push(played);
+                    undone.write(MoveUndone {
+                        side: played.side,
+                        mv: played.mv,
+                    });
+                    if state.is_human_turn() {
+                        break;
+                    }
+                }
+            }
+            MatchCommand::Redo => {
+                while state.can_redo() {
+                    let Some(played) = state.undone.pop() else {
+                        break;
+                    };
+                    state.advance(played);
+                    redone.write(MoveRedone {
+                        side: played.side,
+                        mv: played.mv,
+                    });
+                    if let Some(result) = played.result {
+                        game_over.write(GameOver { result });
+                        break;
+                    }
+                    if state.is_human_turn() {
+                        break;
+                    }
+                }
+            }
+            MatchCommand::Resign => {
+                if state.is_over() {
+                    continue;
+                }
+                let result = GameResult::Win {
+                    winner: G::opponent(state.human_side()),
+                    reason: WinReason::Resignation,
+                };
+                state.result = Some(result);
+                game_over.write(GameOver { result });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    /// A game with no board, whose moves are just numbers.
+    pub(super) struct TestGame;
+
+    impl TwoPlayerGame for TestGame {
+        type Side = u8;
+        type Move = u8;
+        type Rules = ();
+
+        const SIDES: [u8; 2] = [0, 1];
+
+        fn plays(_: GameId) -> bool {
+            false
+        }
+
+        fn side_name(side: u8) -> &'static str {
+            if side == 0 {
+                "First"
+            } else {
+                "Second"
+            }
+        }
+
+        fn rules_label(_: ()) -> String {
+            String::new()
+        }
+    }
+
+    /// An app with a new match of [`TestGame`] under `mode` and `time_control`.
+    pub(super) fn new_match(mode: GameMode, time_control: TimeControl) -> App {
+        let mut app = App::new();
+        app.add_message::<MoveCompleted<TestGame>>()
+            .add_message::<MoveUndone<TestGame>>()
+            .add_message::<MoveRedone<TestGame>>()
+            .add_message::<TurnChanged<TestGame>>()
+            .add_message::<GameOver<TestGame>>()
+            .add_message::<MatchCommand>()
+            .insert_resource(mode)
+            .insert_resource(time_control)
+            .init_resource::<Match<TestGame>>()
+            .add_systems(
+                Update,
+                (
+                    complete_moves::<TestGame>,
+                    handle_match_commands::<TestGame>,
+                )
+                    .chain(),
+            );
+        app.world_mut()
+            .run_system_once(reset_match::<TestGame>)
+            .unwrap();
+        app
+    }
+
+    fn play(app: &mut App, mv: u8, blocked: Blocked) {
+        app.world_mut()
+            .resource_mut::<Match<TestGame>>()
+            .start_move();
+        app.world_mut().write_message(MoveCompleted::<TestGame> {
+            mv,
+            blocked,
+            goal_reached: false,
+        });
+        app.update();
+    }
+
+    fn command(app: &mut App, command: MatchCommand) {
+        app.world_mut().write_message(command);
+        app.update();
+    }
+
+    fn state(app: &App) -> &Match<TestGame> {
+        app.world().resource::<Match<TestGame>>()
+    }
+
+    #[test]
+    fn moves_pass_the_turn() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::No);
+        assert_eq!(state(&app).turn(), 1);
+        assert!(!state(&app).is_moving());
+        play(&mut app, 2, Blocked::OpponentPasses);
+        assert_eq!(state(&app).turn(), 1);
+        assert_eq!(state(&app).moves_played(), 2);
+    }
+
+    #[test]
+    fn undo_and_redo_one_move_at_a_time_between_people() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::No);
+        play(&mut app, 2, Blocked::No);
+
+        command(&mut app, MatchCommand::Undo);
+        assert_eq!(state(&app).moves_played(), 1);
+        assert_eq!(state(&app).turn(), 1);
+        assert_eq!(state(&app).last_move(), Some(1));
+
+        command(&mut app, MatchCommand::Redo);
+        assert_eq!(state(&app).moves_played(), 2);
+        assert_eq!(state(&app).turn(), 0);
+        assert_eq!(state(&app).last_move(), Some(2));
+        assert!(!state(&app).can_redo());
+    }
+
+    #[test]
+    fn undo_against_the_computer_takes_back_its_reply() {
+        let mut app = new_match(GameMode::VsComputer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::No);
+        play(&mut app, 2, Blocked::No);
+
+        command(&mut app, MatchCommand::Undo);
+        assert_eq!(state(&app).moves_played(), 0);
+        assert_eq!(state(&app).turn(), 0);
+        assert!(state(&app).is_human_turn());
+
+        command(&mut app, MatchCommand::Redo);
+        assert_eq!(state(&app).moves_played(), 2);
+        assert_eq!(state(&app).turn(), 0);
+    }
+
+    #[test]
+    fn undo_reopens_a_finished_game() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::OpponentLoses);
+        assert_eq!(
+            state(&app).result(),
+            Some(GameResult::Win {
+                winner: 0,
+                reason: WinReason::OpponentBlocked,
+            })
+        );
+
+        command(&mut app, MatchCommand::Undo);
+        assert!(!state(&app).is_over());
+        assert_eq!(state(&app).turn(), 0);
+
+        command(&mut app, MatchCommand::Redo);
+        assert!(state(&app).is_over());
+    }
+
+    #[test]
+    fn a_new_move_clears_the_moves_to_redo() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::No);
+        command(&mut app, MatchCommand::Undo);
+        assert!(state(&app).can_redo());
+        play(&mut app, 3, Blocked::No);
+        assert!(!state(&app).can_redo());
+        assert_eq!(state(&app).last_move(), Some(3));
+    }
+
+    #[test]
+    fn timed_games_cannot_be_taken_back() {
+        let mut app = new_match(GameMode::VsPlayer, TimeControl::Minutes(1));
+        play(&mut app, 1, Blocked::No);
+        command(&mut app, MatchCommand::Undo);
+        assert_eq!(state(&app).moves_played(), 1);
+    }
+
+    #[test]
+    fn resigning_loses_for_the_person_playing() {
+        let mut app = new_match(GameMode::VsComputer, TimeControl::Untimed);
+        play(&mut app, 1, Blocked::No);
+        command(&mut app, MatchCommand::Resign);
+        assert_eq!(state(&app).result().and_then(GameResult::winner), Some(1));
+    }
+}
@@ -0,0 +1,268 @@
+//! The HUD (turn indicator, rules, clocks, undo and redo) and the results screen.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    menus::Menu,
+    screens::{
+        gameplay::{HUD_FOOTER_HEIGHT, HUD_HEIGHT, PAUSE_BUTTON_CLEARANCE},
+        is_playing, InGame, Screen,
+    },
+    theme::{navigation::SkipNavigation, themes::ThemeColor, widget},
+};
+
+use super::{is_playing_game, Match, MatchCommand, SetUpMatch, TwoPlayerGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, resize_turn_indicator.run_if(is_playing));
+}
+
+pub(super) fn game_plugin<G: TwoPlayerGame>(app: &mut App) {
+    app.add_systems(
+        OnEnter(InGame),
+        spawn_hud::<G>
+            .after(SetUpMatch)
+            .run_if(is_playing_game::<G>),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_turn_indicator::<G>,
+            update_rules_label::<G>,
+            update_clocks::<G>,
+            update_results_screen::<G>,
+        )
+            .run_if(is_playing_game::<G>),
+    );
+}
+
+#[derive(Component)]
+struct TurnIndicator;
+
+/// Names the rule variants the game is played under, if it has any.
+#[derive(Component)]
+struct RulesLabel;
+
+/// The time left for the side at this index in [`TwoPlayerGame::SIDES`].
+#[derive(Component)]
+struct ClockLabel(usize);
+
+#[derive(Component)]
+struct ResultsScreen;
+
+const TURN_INDICATOR_FONT_SIZE: f32 = 32.0;
+const TURN_INDICATOR_MIN_FONT_SIZE: f32 = 18.0;
+/// Size of the line under the turn indicator naming the game's rule variants.
+const RULES_FONT_SIZE: f32 = 16.0;
+
+fn spawn_hud<G: TwoPlayerGame>(
+    mut commands: Commands,
+    state: Res<Match<G>>,
+    screen: Res<State<Screen>>,
+) {
+    let screen = screen.get().clone();
+    commands.spawn((
+        Name::new("Turn Indicator"),
+        // Fill the HUD band, keeping clear of the pause button on both sides so
+        // the text stays centred. Long text wraps rather than running under it.
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            height: Val::Px(HUD_HEIGHT),
+            left: Val::Px(PAUSE_BUTTON_CLEARANCE),
+            right: Val::Px(PAUSE_BUTTON_CLEARANCE),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        StateScoped(screen.clone()),
+        children![
+            (
+                TurnIndicator,
+                Text(turn_text(&state)),
+                TextFont::from_font_size(TURN_INDICATOR_FONT_SIZE),
+                TextColor(Color::WHITE),
+                ThemeColor::HeaderText,
+                TextLayout::new_with_justify(Justify::Center),
+                Label,
+            ),
+            (
+                Name::new("Rules"),
+                RulesLabel,
+                Text::default(),
+                TextFont::from_font_size(RULES_FONT_SIZE),
+                TextColor(Color::WHITE),
+                ThemeColor::LabelText,
+                TextLayout::new_with_justify(Justify::Center),
+                Label,
+            ),
+        ],
+    ));
+
+    let timed = state.clocks.is_some();
+    commands.spawn((
+        Name::new("HUD Footer"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            height: Val::Px(HUD_FOOTER_HEIGHT),
+            width: Val::Percent(100.0),
+            padding: UiRect::horizontal(Val::Px(20.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        // Gamepad navigation moves around the board during play.
+        SkipNavigation,
+        StateScoped(screen),
+        children![
+            (widget::label(""), ClockLabel(0)),
+            (
+                Name::new("Undo Buttons"),
+                Node {
+                    // Timed games can't be taken back.
+                    display: if timed { Display::None } else { Display::Flex },
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    widget::button_medium("Undo", send(MatchCommand::Undo)),
+                    widget::button_medium("Redo", send(MatchCommand::Redo)),
+                ],
+            ),
+            (widget::label(""), ClockLabel(1)),
+        ],
+    ));
+}
+
+fn send(command: MatchCommand) -> impl Fn(On<Pointer<Click>>, MessageWriter<MatchCommand>) {
+    move |_, mut commands| {
+        commands.write(command);
+    }
+}
+
+fn turn_text<G: TwoPlayerGame>(state: &Match<G>) -> String {
+    match state.result() {
+        Some(result) => result.headline::<G>(),
+        None => format!("{}'s Turn", G::side_name(state.turn())),
+    }
+}
+
+fn update_turn_indicator<G: TwoPlayerGame>(
+    state: Res<Match<G>>,
+    mut text: Single<&mut Text, With<TurnIndicator>>,
+) {
+    let new_text = turn_text(&state);
+    // Only touch the text when it changes, so screen readers aren't sent the
+    // same label every frame.
+    if text.0 != new_text {
+        text.0 = new_text;
+    }
+}
+
+fn update_rules_label<G: TwoPlayerGame>(
+    state: Res<Match<G>>,
+    mut text: Single<&mut Text, With<RulesLabel>>,
+) {
+    let rules = state.rules().map(G::rules_label).unwrap_or_default();
+    if text.0 != rules {
+        text.0 = rules;
+    }
+}
+
+/// Shrinks the turn indicator text on narrow windows.
+fn resize_turn_indicator(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut fonts: Query<&mut TextFont, With<TurnIndicator>>,
+) {
+    let size =
+        (window.width() / 24.0).clamp(TURN_INDICATOR_MIN_FONT_SIZE, TURN_INDICATOR_FONT_SIZE);
+    for mut font in &mut fonts {
+        if font.font_size != size {
+            font.font_size = size;
+        }
+    }
+}
+
+fn update_clocks<G: TwoPlayerGame>(
+    state: Res<Match<G>>,
+    mut labels: Query<(&ClockLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut labels {
+        let side = G::SIDES[label.0];
+        let new_text = state.time_left(side).map_or_else(String::new, |seconds| {
+            // Round up, so the clock reads 0:00 only once time is up.
+            let seconds = seconds.ceil() as u32;
+            format!(
+                "{} {}:{:02}",
+                G::side_name(side),
+                seconds / 60,
+                seconds % 60
+            )
+        });
+        if text.0 != new_text {
+            text.0 = new_text;
+        }
+    }
+}
+
+/// Shows the results screen while the game is over. Undoing the last move
+/// takes it away again.
+fn update_results_screen<G: TwoPlayerGame>(
+    mut commands: Commands,
+    state: Res<Match<G>>,
+    screen: Res<State<Screen>>,
+    screens: Query<Entity, With<ResultsScreen>>,
+) {
+    match (state.result(), screens.single()) {
+        (Some(result), Err(_)) => {
+            let undo = state
+                .can_undo()
+                .then(|| widget::button("Undo", send(MatchCommand::Undo)));
+            let mut screen = commands.spawn((
+                Name::new("Results Screen"),
+                ResultsScreen,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                GlobalZIndex(3),
+                StateScoped(screen.get().clone()),
+                children![
+                    widget::header(result.headline::<G>()),
+                    widget::label(result.reason::<G>()),
+                ],
+            ));
+            let rules = state.rules().map(G::rules_label);
+            screen.with_children(|parent| {
+                if let Some(rules) = rules {
+                    parent.spawn(widget::label(rules));
+                }
+                if let Some(undo) = undo {
+                    parent.spawn(undo);
+                }
+                parent.spawn(widget::button("Main Menu", return_to_main_menu));
+            });
+        }
+        (None, Ok(entity)) => {
+            commands.entity(entity).despawn();
+        }
+        _ => {}
+    }
+}
+
+fn return_to_main_menu(
+    _: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_screen.set(Screen::MainMenu);
+    next_menu.set(Menu::GameSelect);
+}
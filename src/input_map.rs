@@ -0,0 +1,296 @@
+//! Named input actions and the keys bound to them.
+//!
+//! Systems ask whether an [`InputAction`] was pressed rather than checking
+//! [`KeyCode`]s directly, so players can rebind keys from the settings menu.
+//! Bindings are saved between sessions.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(InputMap::load());
+    app.add_systems(
+        Update,
+        save_input_map.run_if(resource_changed::<InputMap>.and(not(resource_added::<InputMap>))),
+    );
+}
+
+/// How many [`InputAction::FocusNode`] actions there are: enough for every
+/// kewai on the largest Mū Tōrere board.
+pub const FOCUS_NODE_ACTIONS: u8 = 12;
+
+/// The most keys that can be bound to one action.
+pub const MAX_KEYS_PER_ACTION: usize = 2;
+
+const SAVE_NAME: &str = "input_map";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum InputAction {
+    /// Pause or unpause the game.
+    Pause,
+    /// Back out: clear the board focus, leave a menu, or pause the game.
+    Cancel,
+    /// Select the focused piece or move it to the focused destination.
+    Confirm,
+    /// Focus the next piece or destination.
+    Next,
+    /// Focus the previous piece or destination.
+    Previous,
+    /// Focus the piece on, or the destination at, a node around the board
+    /// (zero-based).
+    FocusNode(u8),
+    /// Focus the piece on, or the destination at, the board's centre node.
+    FocusCenter,
+    /// Take back the last move.
+    Undo,
+    /// Play a move that was taken back again.
+    Redo,
+    /// Suggest a move to play.
+    Hint,
+    /// Toggle the UI debug overlay (dev builds only).
+    ToggleDebugUi,
+}
+
+impl InputAction {
+    /// Every action that can be rebound, in the order the settings menu lists them.
+    pub fn all() -> Vec<InputAction> {
+        let mut actions = vec![
+            InputAction::Pause,
+            InputAction::Cancel,
+            InputAction::Confirm,
+            InputAction::Next,
+            InputAction::Previous,
+        ];
+        actions.extend((0..FOCUS_NODE_ACTIONS).map(InputAction::FocusNode));
+        actions.extend([
+            InputAction::FocusCenter,
+            InputAction::Undo,
+            InputAction::Redo,
+            InputAction::Hint,
+        ]);
+        if cfg!(feature = "dev") {
+            actions.push(InputAction::ToggleDebugUi);
+        }
+        actions
+    }
+
+    /// A short name for the action, for the settings menu.
+    pub fn label(self) -> String {
+        match self {
+            InputAction::Pause => "Pause".to_string(),
+            InputAction::Cancel => "Cancel / Back".to_string(),
+            InputAction::Confirm => "Confirm".to_string(),
+            InputAction::Next => "Next".to_string(),
+            InputAction::Previous => "Previous".to_string(),
+            InputAction::FocusNode(node) => format!("Node {}", node + 1),
+            InputAction::FocusCenter => "Centre Node".to_string(),
+            InputAction::Undo => "Undo".to_string(),
+            InputAction::Redo => "Redo".to_string(),
+            InputAction::Hint => "Hint".to_string(),
+            InputAction::ToggleDebugUi => "Debug UI".to_string(),
+        }
+    }
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            InputAction::Pause => vec![KeyCode::KeyP],
+            InputAction::Cancel => vec![KeyCode::Escape],
+            InputAction::Confirm => vec![KeyCode::Enter, KeyCode::NumpadEnter],
+            InputAction::Next => vec![KeyCode::ArrowRight, KeyCode::ArrowDown],
+            InputAction::Previous => vec![KeyCode::ArrowLeft, KeyCode::ArrowUp],
+            InputAction::FocusNode(node) => {
+                // The number row, with 0 left for the centre.
+                const KEYS: [&[KeyCode]; FOCUS_NODE_ACTIONS as usize] = [
+                    &[KeyCode::Digit1, KeyCode::Numpad1],
+                    &[KeyCode::Digit2, KeyCode::Numpad2],
+                    &[KeyCode::Digit3, KeyCode::Numpad3],
+                    &[KeyCode::Digit4, KeyCode::Numpad4],
+                    &[KeyCode::Digit5, KeyCode::Numpad5],
+                    &[KeyCode::Digit6, KeyCode::Numpad6],
+                    &[KeyCode::Digit7, KeyCode::Numpad7],
+                    &[KeyCode::Digit8, KeyCode::Numpad8],
+                    &[KeyCode::Digit9, KeyCode::Numpad9],
+                    &[KeyCode::Minus],
+                    &[KeyCode::Equal],
+                    &[KeyCode::Backslash],
+                ];
+                KEYS.get(node as usize)
+                    .map_or_else(Vec::new, |keys| keys.to_vec())
+            }
+            InputAction::FocusCenter => vec![KeyCode::Digit0, KeyCode::Numpad0],
+            InputAction::Undo => vec![KeyCode::KeyZ, KeyCode::Backspace],
+            InputAction::Redo => vec![KeyCode::KeyY],
+            InputAction::Hint => vec![KeyCode::KeyH],
+            InputAction::ToggleDebugUi => vec![KeyCode::Backquote],
+        }
+    }
+}
+
+/// The keys bound to each [`InputAction`].
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: BTreeMap<InputAction, Vec<KeyCode>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            // The debug toggle keeps its key even in builds that don't list it.
+            bindings: InputAction::all()
+                .into_iter()
+                .chain([InputAction::ToggleDebugUi])
+                .map(|action| (action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    /// Loads the saved bindings on top of the defaults, so actions added since
+    /// the bindings were saved still get their default keys.
+    fn load() -> Self {
+        let mut map = Self::default();
+        if let Some(saved) = persistence::load::<InputMap>(SAVE_NAME) {
+            map.bindings.extend(saved.bindings);
+        }
+        map
+    }
+
+    /// The keys bound to `action`.
+    pub fn keys(&self, action: InputAction) -> &[KeyCode] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns true if any key bound to `action` was pressed this frame.
+    pub fn just_pressed(&self, action: InputAction, input: &ButtonInput<KeyCode>) -> bool {
+        input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    /// Returns the action other than `action` that `key` is already bound to, if any.
+    pub fn conflict(&self, action: InputAction, key: KeyCode) -> Option<InputAction> {
+        self.bindings
+            .iter()
+            .find(|&(&other, keys)| other != action && keys.contains(&key))
+            .map(|(&other, _)| other)
+    }
+
+    /// Binds `key` to `action` in the given slot (`0..MAX_KEYS_PER_ACTION`).
+    pub fn bind(&mut self, action: InputAction, slot: usize, key: KeyCode) {
+        let keys = self.bindings.entry(action).or_default();
+        keys.retain(|&k| k != key);
+        let slot = slot.min(keys.len()).min(MAX_KEYS_PER_ACTION - 1);
+        if slot < keys.len() {
+            keys[slot] = key;
+        } else {
+            keys.push(key);
+        }
+    }
+
+    /// Removes whatever key is in the given slot of `action`.
+    pub fn clear(&mut self, action: InputAction, slot: usize) {
+        if let Some(keys) = self.bindings.get_mut(&action)
+            && slot < keys.len()
+        {
+            keys.remove(slot);
+        }
+    }
+}
+
+/// A run condition that is true when a key bound to `action` was just pressed.
+pub fn action_just_pressed(
+    action: InputAction,
+) -> impl FnMut(Res<ButtonInput<KeyCode>>, Res<InputMap>) -> bool + Clone {
+    move |input: Res<ButtonInput<KeyCode>>, map: Res<InputMap>| map.just_pressed(action, &input)
+}
+
+/// A readable name for a key, e.g. "P" rather than "KeyP".
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .map_or_else(|| name.clone(), str::to_string)
+}
+
+fn save_input_map(map: Res<InputMap>) {
+    persistence::save(SAVE_NAME, &*map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_replaces_the_key_in_a_slot() {
+        let mut map = InputMap::default();
+        map.bind(InputAction::Pause, 0, KeyCode::KeyQ);
+        assert_eq!(map.keys(InputAction::Pause), [KeyCode::KeyQ]);
+        map.bind(InputAction::Confirm, 1, KeyCode::Space);
+        assert_eq!(
+            map.keys(InputAction::Confirm),
+            [KeyCode::Enter, KeyCode::Space]
+        );
+    }
+
+    #[test]
+    fn bind_fills_the_next_free_slot() {
+        let mut map = InputMap::default();
+        map.bind(InputAction::Pause, 1, KeyCode::KeyQ);
+        assert_eq!(map.keys(InputAction::Pause), [KeyCode::KeyP, KeyCode::KeyQ]);
+        // Slots past the last one replace it.
+        map.bind(InputAction::Pause, 5, KeyCode::KeyW);
+        assert_eq!(map.keys(InputAction::Pause), [KeyCode::KeyP, KeyCode::KeyW]);
+    }
+
+    #[test]
+    fn bind_moves_a_key_already_on_the_action() {
+        let mut map = InputMap::default();
+        map.bind(InputAction::Next, 0, KeyCode::ArrowDown);
+        assert_eq!(map.keys(InputAction::Next), [KeyCode::ArrowDown]);
+    }
+
+    #[test]
+    fn clear_empties_a_slot() {
+        let mut map = InputMap::default();
+        map.clear(InputAction::Undo, 0);
+        assert_eq!(map.keys(InputAction::Undo), [KeyCode::Backspace]);
+        map.clear(InputAction::Undo, 3);
+        assert_eq!(map.keys(InputAction::Undo), [KeyCode::Backspace]);
+    }
+
+    #[test]
+    fn conflict_names_the_other_action() {
+        let map = InputMap::default();
+        assert_eq!(
+            map.conflict(InputAction::Pause, KeyCode::KeyZ),
+            Some(InputAction::Undo)
+        );
+        // A key bound to the action itself isn't a conflict.
+        assert_eq!(map.conflict(InputAction::Undo, KeyCode::KeyZ), None);
+        assert_eq!(map.conflict(InputAction::Pause, KeyCode::KeyQ), None);
+    }
+
+    #[test]
+    fn default_keys_do_not_conflict() {
+        let map = InputMap::default();
+        for action in InputAction::all() {
+            for &key in map.keys(action) {
+                assert_eq!(map.conflict(action, key), None, "{key:?} on {action:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reset_restores_the_default_keys() {
+        let mut map = InputMap::default();
+        map.bind(InputAction::Hint, 0, KeyCode::KeyQ);
+        map.clear(InputAction::Redo, 0);
+        assert_ne!(map, InputMap::default());
+        map = InputMap::default();
+        assert_eq!(map.keys(InputAction::Hint), [KeyCode::KeyH]);
+        assert_eq!(map.keys(InputAction::Redo), [KeyCode::KeyY]);
+    }
+}
@@ -4,10 +4,14 @@
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
 mod asset_tracking;
+mod audio;
 #[cfg(feature = "dev")]
 mod dev_tools;
+mod gamepad;
 mod games;
+mod input_map;
 mod menus;
+mod persistence;
 mod screens;
 mod theme;
 
@@ -42,12 +46,24 @@ impl Plugin for AppPlugin {
                 }),
         );
 
+        // Graph boards are drawn with meshes and picked through them. Only meshes
+        // marked `Pickable` are picked, so the rest of the board art never gets
+        // in the way of a click.
+        app.add_plugins(MeshPickingPlugin);
+        app.insert_resource(MeshPickingSettings {
+            require_markers: true,
+            ..default()
+        });
+
         // Add other plugins.
         app.add_plugins((
             asset_tracking::plugin,
+            audio::plugin,
             #[cfg(feature = "dev")]
             dev_tools::plugin,
+            gamepad::plugin,
             games::plugin,
+            input_map::plugin,
             menus::plugin,
             screens::plugin,
             theme::plugin,
@@ -95,5 +111,5 @@ pub struct Pause(pub bool);
 pub struct PausableSystems;
 
 fn spawn_camera(mut commands: Commands) {
-    commands.spawn((Name::new("Camera"), Camera2d));
+    commands.spawn((Name::new("Camera"), Camera2d, MeshPickingCamera));
 }
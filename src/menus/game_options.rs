@@ -1,13 +1,15 @@
 //! Game options menu - shown after selecting a game, before starting it.
-//! Displays game-specific options (e.g., vs Player / vs Computer for Mu Torere).
+//! Displays the selected game's settings and options from the [`GameRegistry`]
+//! (e.g., the board size and vs Player / vs Computer for Mu Torere).
 
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
 use crate::{
     asset_tracking::ResourceHandles,
-    games::mu_torere::{GameMode, GameSettings},
+    gamepad::gamepad_just_pressed,
+    games::registry::{GameId, GameRegistry, GameSetting, OptionAction},
     menus::Menu,
-    screens::{ActiveGame, Screen},
+    screens::Screen,
     theme::widget,
 };
 
@@ -15,65 +17,129 @@ use super::game_select::SelectedGame;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::GameOptions), spawn_game_options_menu);
+    app.add_systems(
+        Update,
+        go_back_to_game_select
+            .run_if(in_state(Menu::GameOptions).and(gamepad_just_pressed(GamepadButton::East))),
+    );
+    app.add_systems(
+        Update,
+        update_setting_values.run_if(in_state(Menu::GameOptions)),
+    );
 }
 
-fn spawn_game_options_menu(mut commands: Commands, selected: Res<SelectedGame>) {
-    let Some(game) = selected.game else {
+fn spawn_game_options_menu(
+    mut commands: Commands,
+    selected: Res<SelectedGame>,
+    registry: Res<GameRegistry>,
+) {
+    let Some(game) = selected.game.and_then(|id| registry.get(id)).cloned() else {
         return;
     };
 
-    match game {
-        ActiveGame::MuTorere => spawn_mu_torere_options(&mut commands),
-        // Add other games here
-    }
-}
-
-fn spawn_mu_torere_options(commands: &mut Commands) {
     commands.spawn((
-        widget::ui_root("Mu Torere Options"),
+        widget::ui_root(format!("{} Options", game.name)),
         GlobalZIndex(2),
         StateScoped(Menu::GameOptions),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(widget::header(game.name));
+            for setting in game.settings {
+                parent.spawn(setting_row(setting));
+            }
+            for option in game.options {
+                match option.action {
+                    OptionAction::Start(apply) => {
+                        parent.spawn(widget::button(option.label, start_on_click(game.id, apply)));
+                    }
+                    OptionAction::Open(menu) => {
+                        parent.spawn(widget::button(option.label, open_menu(menu)));
+                    }
+                }
+            }
+            parent.spawn(widget::button("Back", go_back));
+        })),
+    ));
+}
+
+/// A setting's label and current value, between buttons that step it back and forth.
+fn setting_row(setting: GameSetting) -> impl Bundle {
+    (
+        Name::new(setting.label),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
         children![
-            widget::header("Mū Tōrere"),
-            widget::button("vs Player", start_vs_player),
-            widget::button("vs Computer", start_vs_computer),
-            widget::button("Back", go_back),
+            widget::label(setting.label),
+            widget::button_small("<", step_setting(setting, -1)),
+            (
+                Name::new("Current Setting"),
+                Node {
+                    justify_content: JustifyContent::Center,
+                    min_width: Val::Px(160.0),
+                    ..default()
+                },
+                children![(widget::label(""), SettingValue(setting))],
+            ),
+            widget::button_small(">", step_setting(setting, 1)),
         ],
-    ));
+    )
 }
 
-fn start_vs_player(
-    _: On<Pointer<Click>>,
-    mut settings: ResMut<GameSettings>,
-    resource_handles: Res<ResourceHandles>,
-    mut next_screen: ResMut<NextState<Screen>>,
-    mut next_menu: ResMut<NextState<Menu>>,
-) {
-    settings.mode = GameMode::VsPlayer;
-    next_menu.set(Menu::None);
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Playing(ActiveGame::MuTorere));
-    } else {
-        next_screen.set(Screen::Loading(ActiveGame::MuTorere));
+/// The text showing a setting's current value.
+#[derive(Component)]
+struct SettingValue(GameSetting);
+
+fn step_setting(setting: GameSetting, step: isize) -> impl Fn(On<Pointer<Click>>, Commands) {
+    move |_, mut commands| {
+        commands.queue(move |world: &mut World| (setting.step)(world, step));
     }
 }
 
-fn start_vs_computer(
-    _: On<Pointer<Click>>,
-    mut settings: ResMut<GameSettings>,
-    resource_handles: Res<ResourceHandles>,
-    mut next_screen: ResMut<NextState<Screen>>,
-    mut next_menu: ResMut<NextState<Menu>>,
-) {
-    settings.mode = GameMode::VsComputer;
-    next_menu.set(Menu::None);
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Playing(ActiveGame::MuTorere));
+fn update_setting_values(world: &mut World) {
+    let mut labels = world.query::<(Entity, &SettingValue)>();
+    let values: Vec<_> = labels
+        .iter(world)
+        .map(|(entity, label)| (entity, (label.0.value)(world)))
+        .collect();
+    for (entity, value) in values {
+        if let Some(mut text) = world.get_mut::<Text>(entity)
+            && text.0 != value
+        {
+            text.0 = value;
+        }
+    }
+}
+
+fn start_on_click(id: GameId, apply: fn(&mut World)) -> impl Fn(On<Pointer<Click>>, Commands) {
+    move |_, mut commands| {
+        commands.queue(move |world: &mut World| start_game(world, id, apply));
+    }
+}
+
+/// Applies an option and starts the game, via the loading screen if its assets aren't ready.
+pub fn start_game(world: &mut World, id: GameId, apply: fn(&mut World)) {
+    apply(world);
+    world.resource_mut::<NextState<Menu>>().set(Menu::None);
+    let screen = if world.resource::<ResourceHandles>().is_done(id) {
+        Screen::Playing(id)
     } else {
-        next_screen.set(Screen::Loading(ActiveGame::MuTorere));
+        Screen::Loading(id)
+    };
+    world.resource_mut::<NextState<Screen>>().set(screen);
+}
+
+fn open_menu(menu: Menu) -> impl Fn(On<Pointer<Click>>, ResMut<NextState<Menu>>) {
+    move |_, mut next_menu| {
+        next_menu.set(menu);
     }
 }
 
-fn go_back(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+fn go_back(_: On<Pointer<Click>>, next_menu: ResMut<NextState<Menu>>) {
+    go_back_to_game_select(next_menu);
+}
+
+fn go_back_to_game_select(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::GameSelect);
 }
@@ -1,11 +1,21 @@
 //! The game selection menu (main menu hub).
+//! Lists every game in the [`GameRegistry`].
 
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{menus::Menu, screens::ActiveGame, theme::widget};
+use crate::{
+    games::registry::{GameId, GameRegistry},
+    menus::Menu,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::GameSelect), spawn_game_select_menu);
+    app.add_systems(
+        Update,
+        refresh_game_select_menu
+            .run_if(in_state(Menu::GameSelect).and(resource_changed::<GameRegistry>)),
+    );
     // Track which game is selected for the options menu
     app.init_resource::<SelectedGame>();
 }
@@ -13,37 +23,54 @@ pub(super) fn plugin(app: &mut App) {
 /// Resource to track which game the player selected (for the options menu)
 #[derive(Resource, Default)]
 pub struct SelectedGame {
-    pub game: Option<ActiveGame>,
+    pub game: Option<GameId>,
 }
 
-fn spawn_game_select_menu(mut commands: Commands) {
+/// The menu, with how many games it lists.
+#[derive(Component)]
+struct GameSelectMenu(usize);
+
+fn spawn_game_select_menu(mut commands: Commands, registry: Res<GameRegistry>) {
+    let games: Vec<_> = registry.iter().map(|game| (game.id, game.name)).collect();
     commands.spawn((
         widget::ui_root("Game Select Menu"),
+        GameSelectMenu(games.len()),
         GlobalZIndex(2),
         StateScoped(Menu::GameSelect),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::header("Select a Game"),
-            widget::button("Mū Tōrere", select_mu_torere),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::header("Select a Game"),
-            widget::button("Mū Tōrere", select_mu_torere),
-            widget::button("Settings", open_settings_menu),
-        ],
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(widget::header("Select a Game"));
+            for (id, name) in games {
+                parent.spawn(widget::button(name, select_game(id)));
+            }
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Exit", exit_app));
+        })),
     ));
 }
 
-fn select_mu_torere(
-    _: On<Pointer<Click>>,
-    mut selected: ResMut<SelectedGame>,
-    mut next_menu: ResMut<NextState<Menu>>,
+/// Lists games registered while the menu is open, e.g. boards that finished loading.
+fn refresh_game_select_menu(
+    mut commands: Commands,
+    menus: Query<(Entity, &GameSelectMenu)>,
+    registry: Res<GameRegistry>,
 ) {
-    selected.game = Some(ActiveGame::MuTorere);
-    next_menu.set(Menu::GameOptions);
+    let Ok((menu, listed)) = menus.single() else {
+        return;
+    };
+    if listed.0 != registry.iter().count() {
+        commands.entity(menu).despawn();
+        spawn_game_select_menu(commands, registry);
+    }
+}
+
+fn select_game(
+    id: GameId,
+) -> impl FnMut(On<Pointer<Click>>, ResMut<SelectedGame>, ResMut<NextState<Menu>>) {
+    move |_, mut selected, mut next_menu| {
+        selected.game = Some(id);
+        next_menu.set(Menu::GameOptions);
+    }
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
@@ -0,0 +1,220 @@
+//! The key bindings menu, reached from the settings menu.
+//!
+//! Click a key slot, then press the key to bind to it. Clicking the slot again
+//! while it waits for a key clears it instead.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    gamepad::gamepad_just_pressed,
+    input_map::{action_just_pressed, key_label, InputAction, InputMap, MAX_KEYS_PER_ACTION},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Rebinding>();
+    app.add_systems(OnEnter(Menu::KeyBindings), spawn_key_bindings_menu);
+    app.add_systems(OnExit(Menu::KeyBindings), reset_rebinding);
+    app.add_systems(
+        Update,
+        (
+            // Go back before capturing, so the key that cancels a capture
+            // doesn't also leave the menu.
+            go_back.run_if(
+                not(is_capturing).and(
+                    action_just_pressed(InputAction::Cancel)
+                        .or(gamepad_just_pressed(GamepadButton::East)),
+                ),
+            ),
+            reset_rebinding.run_if(gamepad_just_pressed(GamepadButton::East)),
+            capture_key,
+            update_binding_labels,
+        )
+            .chain()
+            .run_if(in_state(Menu::KeyBindings)),
+    );
+}
+
+/// Pressing this while waiting for a key cancels instead of binding it.
+const CANCEL_CAPTURE_KEY: KeyCode = KeyCode::Escape;
+
+/// Label shown for an empty key slot.
+const UNBOUND_LABEL: &str = "-";
+
+/// The key slot waiting for a key press, if any, and feedback for the player.
+#[derive(Resource, Default)]
+struct Rebinding {
+    slot: Option<(InputAction, usize)>,
+    message: String,
+}
+
+fn is_capturing(rebinding: Res<Rebinding>) -> bool {
+    rebinding.slot.is_some()
+}
+
+/// Marks the button showing one key slot of an action.
+#[derive(Component)]
+struct BindingSlot {
+    action: InputAction,
+    slot: usize,
+}
+
+#[derive(Component)]
+struct RebindingMessage;
+
+fn spawn_key_bindings_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Key Bindings Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::KeyBindings),
+        children![
+            widget::header("Key Bindings"),
+            bindings_grid(),
+            (widget::label(""), RebindingMessage),
+            (
+                Name::new("Key Bindings Buttons"),
+                Node {
+                    column_gap: px(20),
+                    ..default()
+                },
+                children![
+                    widget::button("Reset to Defaults", reset_to_defaults),
+                    widget::button("Back", go_back_on_click),
+                ],
+            ),
+        ],
+    ));
+}
+
+/// Two columns of actions, each with its key slots.
+fn bindings_grid() -> impl Bundle {
+    let column = [170.0, 120.0, 120.0].map(|width| RepeatedGridTrack::px(1, width));
+    (
+        Name::new("Key Bindings Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(8),
+            column_gap: px(12),
+            grid_template_columns: [column.clone(), column].concat(),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            for action in InputAction::all() {
+                parent.spawn((
+                    widget::label(action.label()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        ..default()
+                    },
+                ));
+                for slot in 0..MAX_KEYS_PER_ACTION {
+                    parent.spawn((
+                        widget::button_medium(UNBOUND_LABEL, click_slot(action, slot)),
+                        BindingSlot { action, slot },
+                    ));
+                }
+            }
+        })),
+    )
+}
+
+/// Starts waiting for a key for the slot, or clears the slot if it was already waiting.
+fn click_slot(
+    action: InputAction,
+    slot: usize,
+) -> impl Fn(On<Pointer<Click>>, ResMut<Rebinding>, ResMut<InputMap>) {
+    move |_, mut rebinding, mut input_map| {
+        if rebinding.slot == Some((action, slot)) {
+            input_map.clear(action, slot);
+            *rebinding = Rebinding::default();
+        } else {
+            rebinding.slot = Some((action, slot));
+            rebinding.message = format!(
+                "Press a key for {} ({} to cancel, click again to clear)",
+                action.label(),
+                key_label(CANCEL_CAPTURE_KEY),
+            );
+        }
+    }
+}
+
+fn capture_key(
+    input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some((action, slot)) = rebinding.slot else {
+        return;
+    };
+    let Some(&key) = input.get_just_pressed().next() else {
+        return;
+    };
+    if key == CANCEL_CAPTURE_KEY {
+        *rebinding = Rebinding::default();
+        return;
+    }
+    // Keep waiting so the player can pick another key.
+    if let Some(other) = input_map.conflict(action, key) {
+        rebinding.message = format!(
+            "{} is already bound to {}. Press another key.",
+            key_label(key),
+            other.label()
+        );
+        return;
+    }
+    input_map.bind(action, slot, key);
+    *rebinding = Rebinding::default();
+}
+
+fn update_binding_labels(
+    input_map: Res<InputMap>,
+    rebinding: Res<Rebinding>,
+    slots: Query<(Entity, &BindingSlot)>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text, Without<RebindingMessage>>,
+    mut message: Single<&mut Text, With<RebindingMessage>>,
+) {
+    for (entity, binding) in &slots {
+        let label = if rebinding.slot == Some((binding.action, binding.slot)) {
+            "...".to_string()
+        } else {
+            input_map
+                .keys(binding.action)
+                .get(binding.slot)
+                .map_or_else(|| UNBOUND_LABEL.to_string(), |&key| key_label(key))
+        };
+        for descendant in children.iter_descendants(entity) {
+            if let Ok(mut text) = texts.get_mut(descendant)
+                && text.0 != label
+            {
+                text.0.clone_from(&label);
+            }
+        }
+    }
+    if message.0 != rebinding.message {
+        message.0.clone_from(&rebinding.message);
+    }
+}
+
+fn reset_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::default();
+}
+
+fn reset_to_defaults(
+    _: On<Pointer<Click>>,
+    mut input_map: ResMut<InputMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    *input_map = InputMap::default();
+    *rebinding = Rebinding::default();
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
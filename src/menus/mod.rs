@@ -2,11 +2,15 @@
 
 mod game_options;
 mod game_select;
+mod key_bindings;
 mod pause;
 mod settings;
 
 use bevy::prelude::*;
 
+pub use game_options::start_game;
+pub use game_select::SelectedGame;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
@@ -14,6 +18,7 @@ pub(super) fn plugin(app: &mut App) {
         game_select::plugin,
         game_options::plugin,
         settings::plugin,
+        key_bindings::plugin,
         pause::plugin,
     ));
 }
@@ -28,6 +33,12 @@ pub enum Menu {
     GameOptions,
     /// Global settings
     Settings,
+    /// Rebinding keys, reached from the settings menu
+    KeyBindings,
+    /// Arranging a custom start position for the selected game, reached from its options
+    StartEditor,
+    /// Choosing a puzzle for the selected game, reached from its options
+    Puzzles,
     /// In-game pause menu
     Pause,
 }
@@ -1,14 +1,23 @@
 //! The pause menu.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    gamepad::gamepad_just_pressed,
+    games::two_player::MatchCommand,
+    input_map::{action_just_pressed, InputAction},
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Pause).and(
+            action_just_pressed(InputAction::Cancel).or(gamepad_just_pressed(GamepadButton::East)),
+        )),
     );
 }
 
@@ -21,6 +30,7 @@ fn spawn_pause_menu(mut commands: Commands) {
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
             widget::button("Settings", open_settings_menu),
+            widget::button("Resign", resign),
             widget::button("Main Menu", quit_to_main_menu),
         ],
     ));
@@ -34,6 +44,15 @@ fn close_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
 
+fn resign(
+    _: On<Pointer<Click>>,
+    mut commands: MessageWriter<MatchCommand>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    commands.write(MatchCommand::Resign);
+    next_menu.set(Menu::None);
+}
+
 fn quit_to_main_menu(
     _: On<Pointer<Click>>,
     mut next_screen: ResMut<NextState<Screen>>,
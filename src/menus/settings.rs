@@ -1,41 +1,68 @@
 //! The settings menu.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::system::IntoObserverSystem, prelude::*};
 
 use crate::{
-    games::mu_torere::GameSettings,
+    audio::AudioSettings,
+    gamepad::gamepad_just_pressed,
+    games::two_player::TimeControl,
+    input_map::{action_just_pressed, InputAction},
     menus::Menu,
     screens::{is_playing, Screen},
-    theme::prelude::*,
+    theme::{
+        accessibility::Accessibility,
+        motion::{AnimationEasing, AnimationSettings, ReducedMotion},
+        prelude::*,
+        slider::{Slider, SliderChanged},
+        themes::{SelectedTheme, Theme, Themes},
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(
+            action_just_pressed(InputAction::Cancel).or(gamepad_just_pressed(GamepadButton::East)),
+        )),
     );
 
     app.add_systems(
         Update,
-        update_instant_animation_label.run_if(in_state(Menu::Settings)),
+        (
+            update_animation_speed_label,
+            sync_animation_speed_slider.run_if(resource_changed::<AnimationSettings>),
+            update_animation_easing_label,
+            update_time_control_label,
+            update_reduced_motion_label,
+            update_theme_label,
+            update_accessibility_labels,
+            update_volume_labels,
+            update_mute_label,
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
-fn spawn_settings_menu(mut commands: Commands) {
+fn spawn_settings_menu(
+    mut commands: Commands,
+    animation: Res<AnimationSettings>,
+    audio_settings: Res<AudioSettings>,
+) {
     commands.spawn((
         widget::ui_root("Settings Menu"),
         GlobalZIndex(2),
         StateScoped(Menu::Settings),
         children![
             widget::header("Settings"),
-            settings_grid(),
+            settings_grid(&animation, &audio_settings),
+            widget::button("Key Bindings", open_key_bindings),
             widget::button("Back", go_back_on_click),
         ],
     ));
 }
 
-fn settings_grid() -> impl Bundle {
+fn settings_grid(animation: &AnimationSettings, audio_settings: &AudioSettings) -> impl Bundle {
     (
         Name::new("Settings Grid"),
         Node {
@@ -46,59 +73,386 @@ fn settings_grid() -> impl Bundle {
             ..default()
         },
         children![
-            (
-                widget::label("Instant Animation"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
+            setting_label("Master Volume"),
+            volume_widget(VolumeChannel::Master, audio_settings),
+            setting_label("Sound Effects"),
+            volume_widget(VolumeChannel::SoundEffects, audio_settings),
+            setting_label("Music"),
+            volume_widget(VolumeChannel::Music, audio_settings),
+            setting_label("Mute"),
+            setting_widget("Mute Widget", MuteLabel, toggle_mute, toggle_mute),
+            setting_label("Animation Speed"),
+            animation_speed_widget(animation.speed()),
+            setting_label("Animation Easing"),
+            setting_widget(
+                "Animation Easing Widget",
+                AnimationEasingLabel,
+                step_animation_easing(-1),
+                step_animation_easing(1),
             ),
-            instant_animation_widget(),
+            setting_label("Clock"),
+            setting_widget(
+                "Clock Widget",
+                TimeControlLabel,
+                step_time_control(-1),
+                step_time_control(1),
+            ),
+            setting_label("Theme"),
+            setting_widget("Theme Widget", ThemeLabel, step_theme(-1), step_theme(1)),
+            setting_label("Reduced Motion"),
+            setting_widget(
+                "Reduced Motion Widget",
+                ReducedMotionLabel,
+                toggle_reduced_motion,
+                toggle_reduced_motion,
+            ),
+            setting_label("Piece Glyphs"),
+            accessibility_toggle(AccessibilityOption::PieceGlyphs),
+            setting_label("Shape Highlights"),
+            accessibility_toggle(AccessibilityOption::ShapeHighlights),
+            setting_label("High-Contrast UI"),
+            accessibility_toggle(AccessibilityOption::HighContrastUi),
         ],
     )
 }
 
-fn instant_animation_widget() -> impl Bundle {
+fn setting_label(text: &str) -> impl Bundle {
+    (
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
+    )
+}
+
+/// A setting's current value between buttons that step it back and forth.
+fn setting_widget<E, B, M, I1, I2>(
+    name: &'static str,
+    label_marker: impl Component,
+    decrease: I1,
+    increase: I2,
+) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I1: IntoObserverSystem<E, B, M>,
+    I2: IntoObserverSystem<E, B, M>,
+{
     (
-        Name::new("Instant Animation Widget"),
+        Name::new(name),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("<", toggle_instant_animation),
+            widget::button_small("<", decrease),
             (
                 Name::new("Current Setting"),
                 Node {
                     padding: UiRect::horizontal(Val::Px(10.0)),
                     justify_content: JustifyContent::Center,
-                    min_width: Val::Px(60.0),
+                    min_width: Val::Px(160.0),
                     ..default()
                 },
-                children![(widget::label(""), InstantAnimationLabel)],
+                children![(widget::label(""), label_marker)],
             ),
-            widget::button_small(">", toggle_instant_animation),
+            widget::button_small(">", increase),
         ],
     )
 }
 
-fn toggle_instant_animation(_: On<Pointer<Click>>, mut settings: ResMut<GameSettings>) {
-    settings.instant_animation = !settings.instant_animation;
+/// Step buttons sit either side of the slider, so it works without a pointer.
+fn animation_speed_widget(speed: f32) -> impl Bundle {
+    (
+        Name::new("Animation Speed Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            widget::button_small("<", step_animation_speed(-ANIMATION_SPEED_STEP)),
+            (
+                widget::slider(speed, set_animation_speed),
+                AnimationSpeedSlider
+            ),
+            widget::button_small(">", step_animation_speed(ANIMATION_SPEED_STEP)),
+            (widget::label(""), AnimationSpeedLabel),
+        ],
+    )
+}
+
+const ANIMATION_SPEED_STEP: f32 = 0.1;
+
+fn set_animation_speed(changed: On<SliderChanged>, mut animation: ResMut<AnimationSettings>) {
+    animation.set_speed(changed.value);
+}
+
+fn step_animation_speed(step: f32) -> impl Fn(On<Pointer<Click>>, ResMut<AnimationSettings>) {
+    move |_, mut animation| {
+        let speed = animation.speed() + step;
+        animation.set_speed(speed);
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AnimationSpeedSlider;
+
+fn sync_animation_speed_slider(
+    animation: Res<AnimationSettings>,
+    slider: Single<&Children, With<AnimationSpeedSlider>>,
+    mut sliders: Query<&mut Slider>,
+) {
+    for child in slider.iter() {
+        if let Ok(mut slider) = sliders.get_mut(child) {
+            let speed = animation.speed();
+            if (slider.value - speed).abs() > f32::EPSILON {
+                slider.value = speed;
+            }
+        }
+    }
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct InstantAnimationLabel;
+struct AnimationSpeedLabel;
 
-fn update_instant_animation_label(
-    settings: Res<GameSettings>,
-    mut label: Single<&mut Text, With<InstantAnimationLabel>>,
+fn update_animation_speed_label(
+    animation: Res<AnimationSettings>,
+    mut label: Single<&mut Text, With<AnimationSpeedLabel>>,
 ) {
-    label.0 = if settings.instant_animation {
-        "On".to_string()
+    let text = if animation.duration <= 0.0 {
+        "Instant".to_string()
     } else {
-        "Off".to_string()
+        format!("{:.2} s", animation.duration)
+    };
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+/// One of the volumes in [`AudioSettings`].
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum VolumeChannel {
+    Master,
+    SoundEffects,
+    Music,
+}
+
+impl VolumeChannel {
+    fn volume(self, settings: &AudioSettings) -> f32 {
+        match self {
+            VolumeChannel::Master => settings.master,
+            VolumeChannel::SoundEffects => settings.sound_effects,
+            VolumeChannel::Music => settings.music,
+        }
+    }
+
+    fn set_volume(self, settings: &mut AudioSettings, volume: f32) {
+        let channel = match self {
+            VolumeChannel::Master => &mut settings.master,
+            VolumeChannel::SoundEffects => &mut settings.sound_effects,
+            VolumeChannel::Music => &mut settings.music,
+        };
+        *channel = volume;
+    }
+}
+
+/// A volume slider, with the volume as a percentage beside it.
+fn volume_widget(channel: VolumeChannel, settings: &AudioSettings) -> impl Bundle {
+    let set_volume = move |changed: On<SliderChanged>, mut settings: ResMut<AudioSettings>| {
+        channel.set_volume(&mut settings, changed.value);
+    };
+    (
+        Name::new("Volume Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            widget::slider(channel.volume(settings), set_volume),
+            (widget::label(""), channel),
+        ],
+    )
+}
+
+fn update_volume_labels(
+    settings: Res<AudioSettings>,
+    mut labels: Query<(&VolumeChannel, &mut Text)>,
+) {
+    for (&channel, mut label) in &mut labels {
+        let text = format!("{:.0}%", channel.volume(&settings) * 100.0);
+        if label.0 != text {
+            label.0 = text;
+        }
+    }
+}
+
+fn toggle_mute(_: On<Pointer<Click>>, mut settings: ResMut<AudioSettings>) {
+    settings.muted = !settings.muted;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct MuteLabel;
+
+fn update_mute_label(settings: Res<AudioSettings>, mut label: Single<&mut Text, With<MuteLabel>>) {
+    let text = if settings.muted { "On" } else { "Off" };
+    if label.0 != text {
+        label.0 = text.to_string();
+    }
+}
+
+fn step_theme(
+    step: isize,
+) -> impl Fn(On<Pointer<Click>>, Option<Res<Themes>>, ResMut<SelectedTheme>) {
+    move |_, themes, mut selected| {
+        // The themes may still be loading.
+        let Some(themes) = themes else {
+            return;
+        };
+        let count = themes.handles.len() as isize;
+        selected.0 = (selected.0 as isize + step).rem_euclid(count) as usize;
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ThemeLabel;
+
+fn update_theme_label(theme: Res<Theme>, mut label: Single<&mut Text, With<ThemeLabel>>) {
+    if label.0 != theme.name {
+        label.0.clone_from(&theme.name);
+    }
+}
+
+fn toggle_reduced_motion(_: On<Pointer<Click>>, mut reduced_motion: ResMut<ReducedMotion>) {
+    reduced_motion.0 = !reduced_motion.0;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReducedMotionLabel;
+
+fn update_reduced_motion_label(
+    reduced_motion: Res<ReducedMotion>,
+    mut label: Single<&mut Text, With<ReducedMotionLabel>>,
+) {
+    let text = if reduced_motion.0 { "On" } else { "Off" };
+    if label.0 != text {
+        label.0 = text.to_string();
+    }
+}
+
+/// One of the on/off settings in [`Accessibility`].
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum AccessibilityOption {
+    PieceGlyphs,
+    ShapeHighlights,
+    HighContrastUi,
+}
+
+impl AccessibilityOption {
+    fn is_on(self, accessibility: &Accessibility) -> bool {
+        match self {
+            AccessibilityOption::PieceGlyphs => accessibility.piece_glyphs,
+            AccessibilityOption::ShapeHighlights => accessibility.shape_highlights,
+            AccessibilityOption::HighContrastUi => accessibility.high_contrast_ui,
+        }
+    }
+
+    fn toggle(self, accessibility: &mut Accessibility) {
+        let value = match self {
+            AccessibilityOption::PieceGlyphs => &mut accessibility.piece_glyphs,
+            AccessibilityOption::ShapeHighlights => &mut accessibility.shape_highlights,
+            AccessibilityOption::HighContrastUi => &mut accessibility.high_contrast_ui,
+        };
+        *value = !*value;
+    }
+}
+
+fn accessibility_toggle(option: AccessibilityOption) -> impl Bundle {
+    let toggle = move |_: On<Pointer<Click>>, mut accessibility: ResMut<Accessibility>| {
+        option.toggle(&mut accessibility);
     };
+    setting_widget("Accessibility Widget", option, toggle, toggle)
+}
+
+fn update_accessibility_labels(
+    accessibility: Res<Accessibility>,
+    mut labels: Query<(&AccessibilityOption, &mut Text)>,
+) {
+    for (&option, mut label) in &mut labels {
+        let text = if option.is_on(&accessibility) {
+            "On"
+        } else {
+            "Off"
+        };
+        if label.0 != text {
+            label.0 = text.to_string();
+        }
+    }
+}
+
+fn open_key_bindings(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::KeyBindings);
+}
+
+fn step_animation_easing(step: isize) -> impl Fn(On<Pointer<Click>>, ResMut<AnimationSettings>) {
+    move |_, mut animation| {
+        let all = AnimationEasing::ALL;
+        let current = all
+            .iter()
+            .position(|&easing| easing == animation.easing)
+            .unwrap_or(0);
+        animation.easing = all[(current as isize + step).rem_euclid(all.len() as isize) as usize];
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AnimationEasingLabel;
+
+fn update_animation_easing_label(
+    animation: Res<AnimationSettings>,
+    mut label: Single<&mut Text, With<AnimationEasingLabel>>,
+) {
+    let text = animation.easing.label();
+    if label.0 != text {
+        label.0 = text.to_string();
+    }
+}
+
+fn step_time_control(step: isize) -> impl Fn(On<Pointer<Click>>, ResMut<TimeControl>) {
+    move |_, mut time_control| {
+        let all = TimeControl::ALL;
+        let current = all
+            .iter()
+            .position(|&choice| choice == *time_control)
+            .unwrap_or(0);
+        *time_control = all[(current as isize + step).rem_euclid(all.len() as isize) as usize];
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct TimeControlLabel;
+
+fn update_time_control_label(
+    time_control: Res<TimeControl>,
+    mut label: Single<&mut Text, With<TimeControlLabel>>,
+) {
+    let text = time_control.label();
+    if label.0 != text {
+        label.0 = text;
+    }
 }
 
 fn go_back_on_click(
@@ -0,0 +1,86 @@
+//! Saving small pieces of player data (settings, progress) between sessions.
+//!
+//! Values are stored as RON: in a per-user config directory on native
+//! platforms, and in the browser's `localStorage` on the web.
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Loads the value saved under `name`, if there is one and it can be read.
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let text = read(name)?;
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Ignoring unreadable saved {name}: {error}");
+            None
+        }
+    }
+}
+
+/// Saves `value` under `name`, replacing anything saved there before.
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, default()) {
+        Ok(text) => write(name, &text),
+        Err(error) => warn!("Failed to save {name}: {error}"),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn save_path(name: &str) -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    #[cfg(target_os = "windows")]
+    let base = env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(target_os = "macos")]
+    let base = env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+    });
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    Some(base?.join("mu_torere").join(format!("{name}.ron")))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(save_path(name)?).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(name: &str, text: &str) {
+    let Some(path) = save_path(name) else {
+        warn!("Nowhere to save {name}");
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, text));
+    if let Err(error) = result {
+        warn!("Failed to save {name} to {}: {error}", path.display());
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn read(name: &str) -> Option<String> {
+    storage()?.get_item(&format!("mu_torere/{name}")).ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn write(name: &str, text: &str) {
+    let saved = storage()
+        .is_some_and(|storage| storage.set_item(&format!("mu_torere/{name}"), text).is_ok());
+    if !saved {
+        warn!("Failed to save {name} to local storage");
+    }
+}
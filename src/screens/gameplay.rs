@@ -1,42 +1,222 @@
 //! The screen state for the main gameplay.
 //! This module handles generic gameplay functionality that applies to all games.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{
+    gamepad::{gamepad_just_pressed, GamepadNavigate, GamepadSeats},
+    input_map::{action_just_pressed, InputAction, InputMap, FOCUS_NODE_ACTIONS},
     menus::Menu,
-    screens::{is_playing, ActiveGame, Screen},
-    Pause,
+    screens::{is_playing, InGame, Screen},
+    theme::{motion::FadeIn, navigation::SkipNavigation, widget},
+    AppSystems, PausableSystems, Pause,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_message::<BoardInput>();
+    app.init_resource::<BoardFocus>();
+    app.init_resource::<BoardBounds>();
+
+    // Zoom the camera so the board fits the window, whatever its size and shape
+    app.add_systems(Update, fit_camera_to_board.in_set(AppSystems::Update));
+
     // Pause/unpause handling - runs for any game
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                is_playing
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                is_playing.and(in_state(Menu::None)).and(
+                    action_just_pressed(InputAction::Pause)
+                        .or(action_just_pressed(InputAction::Cancel).and(not(board_has_focus)))
+                        .or(gamepad_just_pressed(GamepadButton::Start)),
+                ),
             ),
             close_menu.run_if(
                 is_playing
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    // Don't swallow the key being bound on the key bindings menu.
+                    .and(not(in_state(Menu::KeyBindings)))
+                    .and(
+                        action_just_pressed(InputAction::Pause)
+                            .or(gamepad_just_pressed(GamepadButton::Start)),
+                    ),
             ),
         ),
     );
 
+    // Keyboard and gamepad control of the board - each game interprets the actions itself
+    app.add_systems(
+        Update,
+        (record_board_actions, record_gamepad_board_actions)
+            .run_if(is_playing.and(in_state(Menu::None)))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+
+    // On-screen pause button, so touch players can reach the pause menu
+    app.add_systems(OnEnter(InGame), spawn_pause_button);
+
     // Clean up when leaving any game
     app.add_systems(
-        OnExit(Screen::Playing(ActiveGame::MuTorere)),
-        (close_menu, unpause),
+        OnExit(InGame),
+        (close_menu, unpause, clear_board_focus, clear_board_bounds),
     );
-    // Add OnExit for other games as they're added
 
     app.add_systems(OnEnter(Menu::None), unpause.run_if(is_playing));
 }
 
+/// Height of the band across the top of the window kept clear of the board for
+/// the HUD (turn indicator, pause button), in logical pixels.
+pub const HUD_HEIGHT: f32 = 84.0;
+
+/// Height of the band across the bottom of the window kept clear of the board
+/// for the clocks and the undo buttons, in logical pixels.
+pub const HUD_FOOTER_HEIGHT: f32 = 56.0;
+
+/// The size, in world units, of the board the active game draws around the
+/// origin. While set, the camera zooms so the board fills the window below the HUD.
+#[derive(Resource, Default)]
+pub struct BoardBounds(pub Option<Vec2>);
+
+fn clear_board_bounds(mut bounds: ResMut<BoardBounds>) {
+    bounds.0 = None;
+}
+
+fn fit_camera_to_board(
+    bounds: Res<BoardBounds>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&mut Projection, &mut Transform), With<Camera2d>>,
+) {
+    let (scale, offset) = match bounds.0 {
+        Some(size) => {
+            let available = Vec2::new(
+                window.width(),
+                window.height() - HUD_HEIGHT - HUD_FOOTER_HEIGHT,
+            )
+            .max(Vec2::ONE);
+            let scale = (size / available).max_element();
+            // Shift the view so the board is centred in the space between the HUD bands.
+            (scale, (HUD_HEIGHT - HUD_FOOTER_HEIGHT) / 2.0 * scale)
+        }
+        None => (1.0, 0.0),
+    };
+
+    let (mut projection, mut transform) = camera.into_inner();
+    let Projection::Orthographic(orthographic) = projection.bypass_change_detection() else {
+        return;
+    };
+    if orthographic.scale != scale {
+        orthographic.scale = scale;
+        projection.set_changed();
+    }
+    if transform.translation.y != offset {
+        transform.translation.y = offset;
+    }
+}
+
+/// A keyboard or gamepad command for the board of the game being played.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct BoardInput {
+    pub action: BoardAction,
+    /// The [seat](GamepadSeats::seat) of the gamepad that sent this, or `None`
+    /// if it may act for whichever side is to move.
+    pub seat: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardAction {
+    /// Move the focus to the next piece or destination.
+    Next,
+    /// Move the focus to the previous piece or destination.
+    Previous,
+    /// Focus the piece on, or the destination at, the given node around the
+    /// board (zero-based). Games number these however they lay their boards out.
+    FocusNode(usize),
+    /// Focus the piece on, or the destination at, the board's centre node.
+    FocusCenter,
+    /// Select the focused piece or move to the focused destination.
+    Confirm,
+    /// Step the focus back, or clear it.
+    Cancel,
+}
+
+/// Whether the game being played has something focused on its board.
+/// While it does, the Cancel key clears the focus instead of pausing the game.
+#[derive(Resource, Default)]
+pub struct BoardFocus(pub bool);
+
+fn board_has_focus(focus: Res<BoardFocus>) -> bool {
+    focus.0
+}
+
+fn record_board_actions(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    focus: Res<BoardFocus>,
+    mut inputs: MessageWriter<BoardInput>,
+) {
+    let pressed = |action| input_map.just_pressed(action, &input);
+    let mut write = |action| {
+        inputs.write(BoardInput { action, seat: None });
+    };
+    if pressed(InputAction::Next) {
+        write(BoardAction::Next);
+    }
+    if pressed(InputAction::Previous) {
+        write(BoardAction::Previous);
+    }
+    for node in 0..FOCUS_NODE_ACTIONS {
+        if pressed(InputAction::FocusNode(node)) {
+            write(BoardAction::FocusNode(node as usize));
+        }
+    }
+    if pressed(InputAction::FocusCenter) {
+        write(BoardAction::FocusCenter);
+    }
+    if pressed(InputAction::Confirm) {
+        write(BoardAction::Confirm);
+    }
+    if focus.0 && pressed(InputAction::Cancel) {
+        write(BoardAction::Cancel);
+    }
+}
+
+fn record_gamepad_board_actions(
+    gamepads: Query<(Entity, &Gamepad)>,
+    seats: Res<GamepadSeats>,
+    mut navigate: MessageReader<GamepadNavigate>,
+    mut inputs: MessageWriter<BoardInput>,
+) {
+    for event in navigate.read() {
+        let action = if event.direction.x - event.direction.y > 0 {
+            BoardAction::Next
+        } else {
+            BoardAction::Previous
+        };
+        inputs.write(BoardInput {
+            action,
+            seat: seats.seat(event.gamepad),
+        });
+    }
+    for (entity, gamepad) in &gamepads {
+        for (button, action) in [
+            (GamepadButton::South, BoardAction::Confirm),
+            (GamepadButton::East, BoardAction::Cancel),
+        ] {
+            if gamepad.just_pressed(button) {
+                inputs.write(BoardInput {
+                    action,
+                    seat: seats.seat(entity),
+                });
+            }
+        }
+    }
+}
+
+fn clear_board_focus(mut focus: ResMut<BoardFocus>) {
+    focus.0 = false;
+}
+
 fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(false));
 }
@@ -55,10 +235,47 @@ fn spawn_pause_overlay(mut commands: Commands) {
         },
         GlobalZIndex(1),
         BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        FadeIn::default(),
         StateScoped(Pause(true)),
     ));
 }
 
+const PAUSE_BUTTON_MARGIN: f32 = 10.0;
+/// Horizontal space the pause button takes from the edge of the window, so
+/// other HUD elements can keep clear of it.
+pub const PAUSE_BUTTON_CLEARANCE: f32 = PAUSE_BUTTON_MARGIN * 2.0 + 64.0;
+
+fn spawn_pause_button(mut commands: Commands, screen: Res<State<Screen>>) {
+    commands.spawn((
+        Name::new("Pause Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(PAUSE_BUTTON_MARGIN),
+            right: Val::Px(PAUSE_BUTTON_MARGIN),
+            ..default()
+        },
+        // Gamepads pause with Start instead.
+        SkipNavigation,
+        StateScoped(screen.get().clone()),
+        children![widget::button_icon("II", "Pause", pause_on_click)],
+    ));
+}
+
+fn pause_on_click(
+    _: On<Pointer<Click>>,
+    commands: Commands,
+    menu: Res<State<Menu>>,
+    next_pause: ResMut<NextState<Pause>>,
+    next_menu: ResMut<NextState<Menu>>,
+) {
+    if *menu.get() != Menu::None {
+        return;
+    }
+    pause(next_pause);
+    spawn_pause_overlay(commands);
+    open_pause_menu(next_menu);
+}
+
 fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Pause);
 }
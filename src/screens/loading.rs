@@ -1,46 +1,200 @@
 //! A loading screen during which game assets are loaded if necessary.
 //! This reduces stuttering, especially for audio on Wasm.
+//!
+//! The screen shows how much has loaded and, if an asset fails to load, says
+//! which one and offers to try again.
 
 use bevy::prelude::*;
 
 use crate::{
     asset_tracking::ResourceHandles,
-    screens::{is_loading, ActiveGame, Screen},
-    theme::prelude::*,
+    menus::Menu,
+    screens::{is_loading, LoadingGame, Screen},
+    theme::{prelude::*, themes::ThemeColor},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        OnEnter(Screen::Loading(ActiveGame::MuTorere)),
-        spawn_loading_screen,
-    );
-    // Add OnEnter for other games as they're added
+    app.add_systems(OnEnter(LoadingGame), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        check_loading_complete.run_if(is_loading.and(all_assets_loaded)),
+        (
+            update_loading_progress,
+            update_loading_errors,
+            check_loading_complete,
+        )
+            .chain()
+            .run_if(is_loading),
     );
 }
 
+/// Width of the progress bar, in pixels.
+const PROGRESS_BAR_WIDTH: f32 = 400.0;
+
 #[derive(Component)]
 struct LoadingScreen;
 
-fn spawn_loading_screen(mut commands: Commands) {
+/// The filled part of the progress bar.
+#[derive(Component)]
+struct ProgressBarFill;
+
+#[derive(Component)]
+struct ProgressLabel;
+
+/// Shown when something fails to load.
+#[derive(Component)]
+struct LoadingErrorPanel;
+
+#[derive(Component)]
+struct LoadingErrorLabel;
+
+fn spawn_loading_screen(mut commands: Commands, screen: Res<State<Screen>>) {
     commands.spawn((
         LoadingScreen,
         widget::ui_root("Loading Screen"),
-        StateScoped(Screen::Loading(ActiveGame::MuTorere)),
-        children![widget::label("Loading...")],
+        StateScoped(screen.get().clone()),
+        children![
+            widget::label("Loading..."),
+            progress_bar(),
+            (widget::label(""), ProgressLabel),
+            (
+                Name::new("Loading Error Panel"),
+                LoadingErrorPanel,
+                Node {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: px(20),
+                    max_width: px(700),
+                    ..default()
+                },
+                children![
+                    (
+                        widget::label(""),
+                        LoadingErrorLabel,
+                        TextLayout::new_with_justify(Justify::Center),
+                    ),
+                    (
+                        Name::new("Loading Error Buttons"),
+                        Node {
+                            column_gap: px(20),
+                            ..default()
+                        },
+                        children![
+                            widget::button("Retry", retry_loading),
+                            widget::button("Main Menu", return_to_main_menu),
+                        ],
+                    ),
+                ],
+            ),
+        ],
     ));
 }
 
-fn check_loading_complete(screen: Res<State<Screen>>, mut next_screen: ResMut<NextState<Screen>>) {
+fn progress_bar() -> impl Bundle {
+    (
+        Name::new("Progress Bar"),
+        Node {
+            width: px(PROGRESS_BAR_WIDTH),
+            height: px(16),
+            ..default()
+        },
+        BackgroundColor(ui_palette::SLIDER_TRACK),
+        ThemeColor::SliderTrack,
+        BorderRadius::MAX,
+        children![(
+            Name::new("Progress Bar Fill"),
+            ProgressBarFill,
+            Node {
+                width: percent(0),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(ui_palette::BUTTON_BACKGROUND),
+            ThemeColor::ButtonBackground,
+            BorderRadius::MAX,
+        )],
+    )
+}
+
+fn update_loading_progress(
+    screen: Res<State<Screen>>,
+    resource_handles: Res<ResourceHandles>,
+    mut fill: Single<&mut Node, With<ProgressBarFill>>,
+    mut label: Single<&mut Text, With<ProgressLabel>>,
+) {
+    let Screen::Loading(game) = screen.get() else {
+        return;
+    };
+    let progress = resource_handles.progress(*game);
+    fill.width = percent(progress.fraction() * 100.0);
+    let text = format!("{} / {} assets", progress.loaded, progress.total);
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn update_loading_errors(
+    screen: Res<State<Screen>>,
+    resource_handles: Res<ResourceHandles>,
+    assets: Res<AssetServer>,
+    mut panel: Single<&mut Node, With<LoadingErrorPanel>>,
+    mut label: Single<&mut Text, With<LoadingErrorLabel>>,
+) {
+    let Screen::Loading(game) = screen.get() else {
+        return;
+    };
+    let failures = resource_handles.failures(*game, &assets);
+    let display = if failures.is_empty() {
+        Display::None
+    } else {
+        Display::Flex
+    };
+    if panel.display != display {
+        panel.display = display;
+    }
+
+    let text = match failures.as_slice() {
+        [] => String::new(),
+        [failure] => format!("Couldn't load {}.\n{}", failure.path, failure.error),
+        [first, rest @ ..] => format!(
+            "Couldn't load {} and {} other file(s).\n{}",
+            first.path,
+            rest.len(),
+            first.error
+        ),
+    };
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn check_loading_complete(
+    screen: Res<State<Screen>>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
     // Transition from Loading(game) to Playing(game)
-    if let Screen::Loading(game) = screen.get() {
+    if let Screen::Loading(game) = screen.get()
+        && resource_handles.is_done(*game)
+    {
         next_screen.set(Screen::Playing(*game));
     }
 }
 
-fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
-    resource_handles.is_all_done()
+fn retry_loading(
+    _: On<Pointer<Click>>,
+    assets: Res<AssetServer>,
+    mut resource_handles: ResMut<ResourceHandles>,
+) {
+    resource_handles.retry_failed(&assets);
+}
+
+fn return_to_main_menu(
+    _: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_screen.set(Screen::MainMenu);
+    next_menu.set(Menu::GameSelect);
 }
@@ -1,24 +1,21 @@
 //! The game's main screen states and transitions between them.
 
-mod gameplay;
+pub mod gameplay;
 mod loading;
 mod main_menu;
 
 use bevy::prelude::*;
 
+use crate::games::registry::GameId;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_computed_state::<LoadingGame>();
+    app.add_computed_state::<InGame>();
 
     app.add_plugins((gameplay::plugin, loading::plugin, main_menu::plugin));
 }
 
-/// The game being played (used as data in Screen variants).
-#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
-pub enum ActiveGame {
-    MuTorere,
-    // Add new games here as variants
-}
-
 /// The game's main screen states.
 #[derive(States, Clone, Eq, PartialEq, Hash, Debug, Default)]
 pub enum Screen {
@@ -26,9 +23,35 @@ pub enum Screen {
     #[default]
     MainMenu,
     /// Loading assets for a specific game
-    Loading(ActiveGame),
+    Loading(GameId),
     /// Playing a specific game
-    Playing(ActiveGame),
+    Playing(GameId),
+}
+
+/// Present while any game's assets are loading, for systems that run on
+/// entering or leaving the loading screen whichever game it is for.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct LoadingGame;
+
+impl ComputedStates for LoadingGame {
+    type SourceStates = Screen;
+
+    fn compute(screen: Screen) -> Option<Self> {
+        matches!(screen, Screen::Loading(_)).then_some(LoadingGame)
+    }
+}
+
+/// Present while any game is being played, for systems that set up and clean
+/// up what all games share.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = Screen;
+
+    fn compute(screen: Screen) -> Option<Self> {
+        matches!(screen, Screen::Playing(_)).then_some(InGame)
+    }
 }
 
 /// Helper function to check if we're playing any game
@@ -0,0 +1,173 @@
+//! Accessibility: settings that don't depend on the chosen theme, and
+//! screen reader support through AccessKit.
+//!
+//! Colour alone shouldn't carry meaning: the [`Accessibility`] options add
+//! shapes and patterns on top of whatever colours the theme uses, or swap the
+//! UI to a fixed high-contrast palette.
+//!
+//! Bevy gives buttons and [`Label`]s accessible names when they are spawned;
+//! this keeps those names up to date as their text changes, and reads out
+//! [`Announce`] messages through a live region.
+
+use accesskit::{Live, Node as AccessKitNode, Role};
+use bevy::{a11y::AccessibilityNode, input_focus::InputFocus, prelude::*};
+
+use super::{
+    navigation::FocusedButton,
+    themes::{Theme, UiTheme},
+};
+use crate::AppSystems;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Accessibility>();
+    app.init_resource::<InputFocus>();
+    app.add_message::<Announce>();
+    app.add_systems(Startup, spawn_announcer);
+    app.add_systems(
+        Update,
+        (
+            sync_text_labels,
+            sync_button_labels,
+            apply_accessible_names,
+            announce,
+            focus_button.run_if(resource_changed::<FocusedButton>),
+            forget_despawned_focus,
+        )
+            .in_set(AppSystems::Update),
+    );
+}
+
+#[derive(Resource, Default)]
+pub struct Accessibility {
+    /// Draw a glyph on each piece (a dot on white, a diamond on black), so the
+    /// sides can be told apart without relying on their brightness.
+    pub piece_glyphs: bool,
+    /// Mark movable pieces and legal destinations with distinct shapes rather
+    /// than only by colour.
+    pub shape_highlights: bool,
+    /// Use [`UiTheme::HIGH_CONTRAST`] for menus and text, whatever the theme.
+    pub high_contrast_ui: bool,
+}
+
+impl UiTheme {
+    /// White and yellow on black, with strongly contrasting buttons.
+    pub const HIGH_CONTRAST: UiTheme = UiTheme {
+        background: Color::BLACK,
+        label_text: Color::WHITE,
+        header_text: Color::srgb(1.0, 1.0, 0.0),
+        button_text: Color::WHITE,
+        button_background: Color::srgb(0.0, 0.19, 0.75),
+        button_hovered_background: Color::srgb(0.0, 0.38, 1.0),
+        button_pressed_background: Color::srgb(0.0, 0.09, 0.44),
+        slider_track: Color::WHITE,
+    };
+}
+
+impl Accessibility {
+    /// The UI colours to use with `theme`.
+    pub fn ui_theme<'a>(&self, theme: &'a Theme) -> &'a UiTheme {
+        if self.high_contrast_ui {
+            &UiTheme::HIGH_CONTRAST
+        } else {
+            &theme.ui
+        }
+    }
+}
+
+/// Text for screen readers to read out, such as whose turn it is.
+#[derive(Message)]
+pub struct Announce(pub String);
+
+/// The live region that [`Announce`] messages are read from.
+#[derive(Component)]
+struct Announcer;
+
+fn spawn_announcer(mut commands: Commands) {
+    let mut node = AccessKitNode::new(Role::Status);
+    node.set_live(Live::Polite);
+    commands.spawn((
+        Name::new("Screen Reader Announcer"),
+        Announcer,
+        AccessibilityNode(node),
+    ));
+}
+
+fn announce(
+    mut announcements: MessageReader<Announce>,
+    mut announcer: Single<&mut AccessibilityNode, With<Announcer>>,
+) {
+    // Only the latest announcement is read if several arrive at once.
+    if let Some(Announce(text)) = announcements.read().last() {
+        announcer.set_label(text.as_str());
+    }
+}
+
+/// Keeps the accessible text of labels (and anything else with its own
+/// accessibility node, like the turn indicator) in step with what they show.
+fn sync_text_labels(mut texts: Query<(&Text, &mut AccessibilityNode), Changed<Text>>) {
+    for (text, mut node) in &mut texts {
+        // Bevy puts a label's text in its value, and other nodes' in their name.
+        if node.role() == Role::Label {
+            if node.value() != Some(text.0.as_str()) {
+                node.set_value(text.0.as_str());
+            }
+        } else if node.label() != Some(text.0.as_str()) {
+            node.set_label(text.0.as_str());
+        }
+    }
+}
+
+/// A name for screen readers to use in place of a button's text, e.g. for an icon.
+#[derive(Component)]
+pub struct AccessibleName(pub String);
+
+/// Bevy names a button after its text when it is spawned, so rename it once
+/// its accessibility node is in place.
+fn apply_accessible_names(
+    mut nodes: Query<(&AccessibleName, &mut AccessibilityNode), Added<AccessibilityNode>>,
+) {
+    for (name, mut node) in &mut nodes {
+        node.set_label(name.0.as_str());
+    }
+}
+
+/// Renames buttons whose text changes, such as the key binding slots.
+fn sync_button_labels(
+    texts: Query<(&Text, &ChildOf), (Changed<Text>, Without<AccessibilityNode>)>,
+    mut buttons: Query<&mut AccessibilityNode, (With<Button>, Without<AccessibleName>)>,
+) {
+    for (text, child_of) in &texts {
+        if let Ok(mut node) = buttons.get_mut(child_of.parent())
+            && node.label() != Some(text.0.as_str())
+        {
+            node.set_label(text.0.as_str());
+        }
+    }
+}
+
+/// Moves the screen reader's focus along with gamepad navigation.
+fn focus_button(
+    focused: Res<FocusedButton>,
+    buttons: Query<(), With<Button>>,
+    mut input_focus: ResMut<InputFocus>,
+) {
+    match focused.0 {
+        Some(entity) => input_focus.set(entity),
+        // Leave the focus alone if it's on something else, like the board.
+        None if input_focus.0.is_some_and(|entity| buttons.contains(entity)) => {
+            input_focus.clear();
+        }
+        None => {}
+    }
+}
+
+/// Bevy stops updating the accessibility tree while the focus is on an
+/// entity without an accessibility node, so let go of despawned ones.
+fn forget_despawned_focus(entities: Query<()>, mut input_focus: ResMut<InputFocus>) {
+    if input_focus
+        .0
+        .is_some_and(|entity| !entities.contains(entity))
+    {
+        input_focus.clear();
+    }
+}
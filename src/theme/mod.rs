@@ -3,8 +3,13 @@
 // Unused utilities may trigger this lints undesirably.
 #![allow(dead_code)]
 
+pub mod accessibility;
 pub mod interaction;
+pub mod motion;
+pub mod navigation;
 pub mod palette;
+pub mod slider;
+pub mod themes;
 pub mod widget;
 
 #[allow(unused_imports)]
@@ -15,5 +20,12 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((
+        accessibility::plugin,
+        interaction::plugin,
+        motion::plugin,
+        navigation::plugin,
+        slider::plugin,
+        themes::plugin,
+    ));
 }
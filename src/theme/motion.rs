@@ -0,0 +1,158 @@
+//! Motion preferences shared by every screen, and small UI motion effects
+//! that respect them.
+
+use bevy::{
+    math::curve::{Curve, EaseFunction},
+    prelude::*,
+};
+
+use crate::AppSystems;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReducedMotion>();
+    app.init_resource::<AnimationSettings>();
+    app.add_observer(start_fade_in);
+    app.add_systems(Update, fade_in.in_set(AppSystems::Update));
+}
+
+/// Accessibility setting: when on, things that would ease, pulse or fade
+/// change in one step instead.
+#[derive(Resource, Default)]
+pub struct ReducedMotion(pub bool);
+
+/// How pieces move across the board, in every game.
+#[derive(Resource)]
+pub struct AnimationSettings {
+    /// How long a piece takes to move, in seconds. Zero moves pieces instantly.
+    pub duration: f32,
+    pub easing: AnimationEasing,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        Self {
+            duration: 0.2,
+            easing: AnimationEasing::default(),
+        }
+    }
+}
+
+impl AnimationSettings {
+    /// The slowest [`duration`](Self::duration) the speed setting allows.
+    pub const MAX_DURATION: f32 = 1.0;
+
+    /// Animation speed in `0..=1`, where 1 is instant.
+    pub fn speed(&self) -> f32 {
+        1.0 - self.duration / Self::MAX_DURATION
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.duration = (1.0 - speed.clamp(0.0, 1.0)) * Self::MAX_DURATION;
+    }
+
+    /// Moves a piece animation's `progress` on by `delta` seconds, up to 1,
+    /// and returns how far along its path the piece should be. That can stray
+    /// outside `0..=1` for easing curves that overshoot. Reduced motion moves
+    /// pieces at a steady speed, with no easing or overshoot.
+    pub fn advance(&self, progress: &mut f32, delta: f32, reduced_motion: &ReducedMotion) -> f32 {
+        *progress = if self.duration <= 0.0 {
+            1.0
+        } else {
+            (*progress + delta / self.duration).min(1.0)
+        };
+        if reduced_motion.0 {
+            *progress
+        } else {
+            self.easing.sample(*progress)
+        }
+    }
+}
+
+/// The speed curve a piece follows as it moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AnimationEasing {
+    Linear,
+    /// Starts fast and slows into the destination.
+    #[default]
+    EaseOut,
+    /// Speeds up, then slows down.
+    EaseInOut,
+    /// Slightly overshoots the destination and settles back.
+    Overshoot,
+}
+
+impl AnimationEasing {
+    pub const ALL: [AnimationEasing; 4] = [
+        AnimationEasing::Linear,
+        AnimationEasing::EaseOut,
+        AnimationEasing::EaseInOut,
+        AnimationEasing::Overshoot,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnimationEasing::Linear => "Linear",
+            AnimationEasing::EaseOut => "Ease Out",
+            AnimationEasing::EaseInOut => "Ease In-Out",
+            AnimationEasing::Overshoot => "Overshoot",
+        }
+    }
+
+    /// Maps linear progress `t` in `0..=1` to eased progress.
+    pub fn sample(self, t: f32) -> f32 {
+        let function = match self {
+            AnimationEasing::Linear => EaseFunction::Linear,
+            AnimationEasing::EaseOut => EaseFunction::QuadraticOut,
+            AnimationEasing::EaseInOut => EaseFunction::CubicInOut,
+            AnimationEasing::Overshoot => EaseFunction::BackOut,
+        };
+        function.sample_clamped(t)
+    }
+}
+
+/// How long a [`FadeIn`] takes, in seconds.
+const FADE_DURATION: f32 = 0.2;
+
+/// Fades an entity's [`BackgroundColor`] in from transparent when it is spawned.
+#[derive(Component, Default)]
+pub struct FadeIn {
+    target_alpha: f32,
+    elapsed: f32,
+}
+
+fn start_fade_in(
+    add: On<Add, FadeIn>,
+    reduced_motion: Res<ReducedMotion>,
+    mut commands: Commands,
+    mut fades: Query<(&mut FadeIn, &mut BackgroundColor)>,
+) {
+    let Ok((mut fade, mut background)) = fades.get_mut(add.entity) else {
+        return;
+    };
+    if reduced_motion.0 {
+        commands.entity(add.entity).remove::<FadeIn>();
+        return;
+    }
+    fade.target_alpha = background.0.alpha();
+    background.0.set_alpha(0.0);
+}
+
+fn fade_in(
+    mut commands: Commands,
+    time: Res<Time>,
+    reduced_motion: Res<ReducedMotion>,
+    mut fades: Query<(Entity, &mut FadeIn, &mut BackgroundColor)>,
+) {
+    for (entity, mut fade, mut background) in &mut fades {
+        fade.elapsed += time.delta_secs();
+        let t = if reduced_motion.0 {
+            1.0
+        } else {
+            (fade.elapsed / FADE_DURATION).min(1.0)
+        };
+        background.0.set_alpha(fade.target_alpha * t);
+        if t >= 1.0 {
+            commands.entity(entity).remove::<FadeIn>();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Gamepad focus navigation between the buttons built by [`widget`](super::widget).
+
+use std::time::Duration;
+
+use bevy::{
+    camera::NormalizedRenderTarget,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerId},
+    },
+    prelude::*,
+};
+
+use crate::{
+    gamepad::{gamepad_just_pressed, GamepadNavigate},
+    theme::interaction::InteractionPalette,
+    AppSystems,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FocusedButton>();
+    app.add_systems(
+        Update,
+        (
+            navigate_buttons,
+            click_focused_button.run_if(gamepad_just_pressed(GamepadButton::South)),
+            highlight_focused_button.run_if(resource_changed::<FocusedButton>),
+        )
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// The button focused by gamepad navigation, if any.
+#[derive(Resource, Default)]
+pub struct FocusedButton(pub Option<Entity>);
+
+/// Keeps gamepad navigation away from the buttons inside this entity, such as
+/// on-screen controls that the gamepad already has its own button for.
+#[derive(Component)]
+pub struct SkipNavigation;
+
+fn navigate_buttons(
+    mut navigate: MessageReader<GamepadNavigate>,
+    buttons: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<Button>>,
+    parents: Query<&ChildOf>,
+    skipped: Query<(), With<SkipNavigation>>,
+    mut focused: ResMut<FocusedButton>,
+) {
+    let visible: Vec<(Entity, Vec2)> = buttons
+        .iter()
+        .filter(|(entity, _, visibility)| {
+            visibility.get()
+                && !skipped.contains(*entity)
+                && !parents
+                    .iter_ancestors(*entity)
+                    .any(|ancestor| skipped.contains(ancestor))
+        })
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect();
+
+    // Forget the focus once its button goes away (e.g. the menu closed).
+    if focused
+        .0
+        .is_some_and(|entity| !visible.iter().any(|&(e, _)| e == entity))
+    {
+        focused.0 = None;
+    }
+
+    for event in navigate.read() {
+        let current = focused
+            .0
+            .and_then(|entity| visible.iter().find(|&&(e, _)| e == entity));
+        let Some(&(_, from)) = current else {
+            // Nothing focused yet: start from the top-left button.
+            focused.0 = visible
+                .iter()
+                .min_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)))
+                .map(|&(entity, _)| entity);
+            continue;
+        };
+
+        // UI space has +Y pointing down.
+        let direction = Vec2::new(event.direction.x as f32, -event.direction.y as f32);
+        // Prefer the nearest button in that direction, penalising sideways distance.
+        let next = visible
+            .iter()
+            .filter_map(|&(entity, position)| {
+                let offset = position - from;
+                let along = offset.dot(direction);
+                let across = offset.perp_dot(direction).abs();
+                (along > 0.0).then_some((entity, along + 2.0 * across))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((entity, _)) = next {
+            focused.0 = Some(entity);
+        }
+    }
+}
+
+fn click_focused_button(focused: Res<FocusedButton>, mut commands: Commands) {
+    let Some(entity) = focused.0 else {
+        return;
+    };
+    // Button actions are observers of `Pointer<Click>`, so pressing South
+    // stands in for a click on the focused button.
+    commands.trigger(Pointer::new(
+        PointerId::Mouse,
+        Location {
+            target: NormalizedRenderTarget::None {
+                width: 0,
+                height: 0,
+            },
+            position: Vec2::ZERO,
+        },
+        Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+            duration: Duration::ZERO,
+        },
+        entity,
+    ));
+}
+
+fn highlight_focused_button(
+    focused: Res<FocusedButton>,
+    mut buttons: Query<
+        (
+            Entity,
+            &Interaction,
+            &InteractionPalette,
+            &mut BackgroundColor,
+        ),
+        With<Button>,
+    >,
+) {
+    for (entity, interaction, palette, mut background) in &mut buttons {
+        *background = match interaction {
+            Interaction::Pressed => palette.pressed,
+            _ if focused.0 == Some(entity) => palette.hovered,
+            Interaction::Hovered => palette.hovered,
+            Interaction::None => palette.none,
+        }
+        .into();
+    }
+}
@@ -1,5 +1,11 @@
+//! The classic theme's UI colours. See [`Theme`](super::themes::Theme) for the
+//! colours actually in use.
+
 use bevy::prelude::*;
 
+/// #2b2c2f
+pub const BACKGROUND: Color = Color::srgb(0.169, 0.173, 0.184);
+
 /// #ddd369
 pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
@@ -14,3 +20,6 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+
+/// #5a5f73
+pub const SLIDER_TRACK: Color = Color::srgb(0.353, 0.373, 0.451);
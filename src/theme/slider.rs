@@ -0,0 +1,133 @@
+//! Behaviour of the sliders built by [`widget::slider`](super::widget::slider).
+
+use accesskit::{Action, Node as AccessKitNode, Role};
+use bevy::{
+    a11y::{AccessibilityNode, ActionRequest},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (handle_slider_actions, update_slider_thumbs).chain(),
+    );
+}
+
+/// How far a screen reader's increment and decrement actions move a slider.
+const SLIDER_STEP: f32 = 0.1;
+
+/// A horizontal slider's value, in `0..=1`.
+/// Setting it moves the thumb without triggering [`SliderChanged`].
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Slider {
+    pub value: f32,
+}
+
+/// Triggered on a slider when the player drags it to a new value.
+#[derive(EntityEvent, Debug)]
+pub struct SliderChanged {
+    pub entity: Entity,
+    pub value: f32,
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SliderThumb;
+
+/// Jumps the slider to where it was pressed.
+pub(super) fn press_slider(
+    press: On<Pointer<Press>>,
+    commands: Commands,
+    sliders: Query<(&mut Slider, &ComputedNode, &UiGlobalTransform)>,
+) {
+    set_from_pointer(
+        press.entity,
+        press.pointer_location.position,
+        commands,
+        sliders,
+    );
+}
+
+pub(super) fn drag_slider(
+    drag: On<Pointer<Drag>>,
+    commands: Commands,
+    sliders: Query<(&mut Slider, &ComputedNode, &UiGlobalTransform)>,
+) {
+    set_from_pointer(
+        drag.entity,
+        drag.pointer_location.position,
+        commands,
+        sliders,
+    );
+}
+
+fn set_from_pointer(
+    entity: Entity,
+    pointer: Vec2,
+    mut commands: Commands,
+    mut sliders: Query<(&mut Slider, &ComputedNode, &UiGlobalTransform)>,
+) {
+    let Ok((mut slider, node, transform)) = sliders.get_mut(entity) else {
+        return;
+    };
+    // Layout is in physical pixels; pointers are in logical pixels.
+    let width = node.size.x * node.inverse_scale_factor;
+    let left = transform.translation.x * node.inverse_scale_factor - width / 2.0;
+    let value = ((pointer.x - left) / width).clamp(0.0, 1.0);
+    if value != slider.value {
+        slider.value = value;
+        commands.trigger(SliderChanged { entity, value });
+    }
+}
+
+/// How a slider appears to screen readers.
+pub(super) fn accessibility_node(value: f32) -> AccessibilityNode {
+    let mut node = AccessKitNode::new(Role::Slider);
+    node.set_min_numeric_value(0.0);
+    node.set_max_numeric_value(1.0);
+    node.set_numeric_value_step(SLIDER_STEP as f64);
+    node.set_numeric_value(value as f64);
+    node.add_action(Action::Increment);
+    node.add_action(Action::Decrement);
+    AccessibilityNode(node)
+}
+
+fn handle_slider_actions(
+    mut requests: MessageReader<ActionRequest>,
+    mut commands: Commands,
+    mut sliders: Query<&mut Slider>,
+) {
+    for request in requests.read() {
+        let step = match request.action {
+            Action::Increment => SLIDER_STEP,
+            Action::Decrement => -SLIDER_STEP,
+            _ => continue,
+        };
+        let Some(entity) = Entity::try_from_bits(request.target.0) else {
+            continue;
+        };
+        let Ok(mut slider) = sliders.get_mut(entity) else {
+            continue;
+        };
+        let value = (slider.value + step).clamp(0.0, 1.0);
+        if value != slider.value {
+            slider.value = value;
+            commands.trigger(SliderChanged { entity, value });
+        }
+    }
+}
+
+fn update_slider_thumbs(
+    mut sliders: Query<(&Slider, &Children, &mut AccessibilityNode), Changed<Slider>>,
+    mut thumbs: Query<&mut Node, With<SliderThumb>>,
+) {
+    for (slider, children, mut accessibility_node) in &mut sliders {
+        accessibility_node.set_numeric_value(slider.value as f64);
+        for child in children.iter() {
+            if let Ok(mut node) = thumbs.get_mut(child) {
+                node.left = percent(slider.value * 100.0);
+            }
+        }
+    }
+}
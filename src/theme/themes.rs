@@ -0,0 +1,288 @@
+//! Colour themes for the UI and the game boards.
+//!
+//! Themes are `*.theme.ron` files in `assets/themes`, loaded as [`Theme`]
+//! assets. The selected one is copied into the [`Theme`] resource, which the
+//! rest of the game reads its colours from. Editing a theme file while the
+//! game runs reloads it in dev builds.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    asset_tracking::LoadResource,
+    theme::{accessibility::Accessibility, interaction::InteractionPalette, palette::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Theme>();
+    app.init_asset_loader::<ThemeLoader>();
+    app.init_resource::<Theme>();
+    app.init_resource::<SelectedTheme>();
+    app.load_resource::<Themes>();
+    app.add_systems(Update, (select_theme, apply_ui_theme).chain());
+}
+
+/// The built-in themes, in the order the theme picker lists them.
+const THEME_PATHS: [&str; 3] = [
+    "themes/classic.theme.ron",
+    "themes/high_contrast.theme.ron",
+    "themes/stone.theme.ron",
+];
+
+/// A set of colours for the UI and the board.
+/// As a resource, this is the theme currently in use.
+#[derive(Asset, Resource, TypePath, Clone, Debug, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub ui: UiTheme,
+    pub board: BoardTheme,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UiTheme {
+    #[serde(deserialize_with = "hex")]
+    pub background: Color,
+    #[serde(deserialize_with = "hex")]
+    pub label_text: Color,
+    #[serde(deserialize_with = "hex")]
+    pub header_text: Color,
+    #[serde(deserialize_with = "hex")]
+    pub button_text: Color,
+    #[serde(deserialize_with = "hex")]
+    pub button_background: Color,
+    #[serde(deserialize_with = "hex")]
+    pub button_hovered_background: Color,
+    #[serde(deserialize_with = "hex")]
+    pub button_pressed_background: Color,
+    #[serde(deserialize_with = "hex")]
+    pub slider_track: Color,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BoardTheme {
+    #[serde(deserialize_with = "hex")]
+    pub line: Color,
+    #[serde(deserialize_with = "hex")]
+    pub node: Color,
+    /// A node the focused or dragged piece can move to.
+    #[serde(deserialize_with = "hex")]
+    pub node_destination: Color,
+    #[serde(deserialize_with = "hex")]
+    pub node_hovered: Color,
+    /// The ring around a piece that can move.
+    #[serde(deserialize_with = "hex")]
+    pub highlight: Color,
+    #[serde(deserialize_with = "hex")]
+    pub highlight_hovered: Color,
+    #[serde(deserialize_with = "hex")]
+    pub focus_ring: Color,
+    #[serde(deserialize_with = "hex")]
+    pub white_piece: Color,
+    #[serde(deserialize_with = "hex")]
+    pub black_piece: Color,
+    #[serde(deserialize_with = "hex")]
+    pub piece_outline: Color,
+    pub piece_outline_width: f32,
+    #[serde(default)]
+    pub piece_shadow: Option<ShadowTheme>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ShadowTheme {
+    #[serde(deserialize_with = "hex")]
+    pub color: Color,
+    pub offset: Vec2,
+}
+
+/// The classic theme, used until the theme files have loaded.
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "Classic".to_string(),
+            ui: UiTheme {
+                background: BACKGROUND,
+                label_text: LABEL_TEXT,
+                header_text: HEADER_TEXT,
+                button_text: BUTTON_TEXT,
+                button_background: BUTTON_BACKGROUND,
+                button_hovered_background: BUTTON_HOVERED_BACKGROUND,
+                button_pressed_background: BUTTON_PRESSED_BACKGROUND,
+                slider_track: SLIDER_TRACK,
+            },
+            board: BoardTheme {
+                line: Color::srgb(0.4, 0.4, 0.4),
+                node: Color::srgb(0.2, 0.2, 0.2),
+                node_destination: Color::srgb(0.3, 0.55, 0.3),
+                node_hovered: Color::srgb(0.3, 0.3, 0.3),
+                highlight: Color::srgb(1.0, 1.0, 0.0),
+                highlight_hovered: Color::srgb(1.0, 1.0, 0.5),
+                focus_ring: Color::srgba(0.2, 0.8, 1.0, 0.9),
+                white_piece: Color::srgb(0.95, 0.95, 0.95),
+                black_piece: Color::srgb(0.1, 0.1, 0.1),
+                piece_outline: Color::srgb(0.5, 0.5, 0.5),
+                piece_outline_width: 2.0,
+                piece_shadow: Some(ShadowTheme {
+                    color: Color::srgba(0.0, 0.0, 0.0, 0.4),
+                    offset: Vec2::new(3.0, -4.0),
+                }),
+            },
+        }
+    }
+}
+
+/// Reads a colour written as a hex string, e.g. `"#4666bf"` or `"#00000066"`.
+fn hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Srgba::hex(&text)
+        .map(Color::Srgba)
+        .map_err(|error| serde::de::Error::custom(format!("bad colour {text:?}: {error}")))
+}
+
+#[derive(Default, TypePath)]
+struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Theme, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+/// Every built-in theme.
+#[derive(Resource, Asset, TypePath, Clone)]
+pub struct Themes {
+    #[dependency]
+    pub handles: Vec<Handle<Theme>>,
+}
+
+impl FromWorld for Themes {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            handles: THEME_PATHS.map(|path| assets.load(path)).to_vec(),
+        }
+    }
+}
+
+/// The index into [`Themes`] of the theme in use.
+#[derive(Resource, Default)]
+pub struct SelectedTheme(pub usize);
+
+/// Copies the selected theme into the [`Theme`] resource when the selection
+/// changes, the themes finish loading, or the theme's file is edited.
+fn select_theme(
+    mut events: MessageReader<AssetEvent<Theme>>,
+    selected: Res<SelectedTheme>,
+    themes: Option<Res<Themes>>,
+    assets: Res<Assets<Theme>>,
+    mut theme: ResMut<Theme>,
+) {
+    let Some(themes) = themes else {
+        return;
+    };
+    let Some(handle) = themes.handles.get(selected.0) else {
+        return;
+    };
+    // Read every event, so old ones don't count as a reload later.
+    let reloaded = events
+        .read()
+        .filter(|event| event.is_modified(handle))
+        .count()
+        > 0;
+    if (reloaded || selected.is_changed() || themes.is_added())
+        && let Some(selected_theme) = assets.get(handle)
+    {
+        *theme = selected_theme.clone();
+    }
+}
+
+/// Which of the theme's UI colours an entity uses for its [`TextColor`] or
+/// [`BackgroundColor`].
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub enum ThemeColor {
+    LabelText,
+    HeaderText,
+    ButtonText,
+    ButtonBackground,
+    SliderTrack,
+}
+
+impl ThemeColor {
+    fn get(self, ui: &UiTheme) -> Color {
+        match self {
+            ThemeColor::LabelText => ui.label_text,
+            ThemeColor::HeaderText => ui.header_text,
+            ThemeColor::ButtonText => ui.button_text,
+            ThemeColor::ButtonBackground => ui.button_background,
+            ThemeColor::SliderTrack => ui.slider_track,
+        }
+    }
+}
+
+/// Colours newly spawned widgets, and every widget when the theme (or the
+/// high-contrast setting) changes.
+fn apply_ui_theme(
+    theme: Res<Theme>,
+    accessibility: Res<Accessibility>,
+    mut clear_color: ResMut<ClearColor>,
+    mut colored: Query<
+        (
+            Ref<ThemeColor>,
+            Option<&mut TextColor>,
+            Option<&mut BackgroundColor>,
+        ),
+        Without<InteractionPalette>,
+    >,
+    mut buttons: Query<(&mut InteractionPalette, &Interaction, &mut BackgroundColor)>,
+) {
+    let ui = accessibility.ui_theme(&theme);
+    let changed = theme.is_changed() || accessibility.is_changed();
+    if changed {
+        clear_color.0 = ui.background;
+    }
+
+    for (role, text, background) in &mut colored {
+        if !changed && !role.is_added() {
+            continue;
+        }
+        let color = role.get(ui);
+        if let Some(mut text) = text {
+            text.0 = color;
+        }
+        if let Some(mut background) = background {
+            background.0 = color;
+        }
+    }
+
+    for (mut palette, interaction, mut background) in &mut buttons {
+        if !changed && !palette.is_added() {
+            continue;
+        }
+        palette.none = ui.button_background;
+        palette.hovered = ui.button_hovered_background;
+        palette.pressed = ui.button_pressed_background;
+        background.0 = match interaction {
+            Interaction::None => palette.none,
+            Interaction::Hovered => palette.hovered,
+            Interaction::Pressed => palette.pressed,
+        };
+    }
+}
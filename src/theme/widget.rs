@@ -7,7 +7,13 @@ use bevy::{
     prelude::*,
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{
+    accessibility::AccessibleName,
+    interaction::InteractionPalette,
+    palette::*,
+    slider::{accessibility_node, drag_slider, press_slider, Slider, SliderChanged, SliderThumb},
+    themes::ThemeColor,
+};
 
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
@@ -35,6 +41,8 @@ pub fn header(text: impl Into<String>) -> impl Bundle {
         Text(text.into()),
         TextFont::from_font_size(40.0),
         TextColor(HEADER_TEXT),
+        ThemeColor::HeaderText,
+        Label,
     )
 }
 
@@ -45,6 +53,8 @@ pub fn label(text: impl Into<String>) -> impl Bundle {
         Text(text.into()),
         TextFont::from_font_size(24.0),
         TextColor(LABEL_TEXT),
+        ThemeColor::LabelText,
+        Label,
     )
 }
 
@@ -57,6 +67,7 @@ where
 {
     button_base(
         text,
+        BUTTON_FONT_SIZE,
         action,
         (
             Node {
@@ -71,6 +82,31 @@ where
     )
 }
 
+/// A medium rounded button with text and an action defined as an [`Observer`].
+/// Sized to fit several to a row, e.g. in a grid of settings.
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        24.0,
+        action,
+        (
+            Node {
+                width: px(120),
+                height: px(36),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BorderRadius::all(px(8)),
+        ),
+    )
+}
+
 /// A small square button with text and an action defined as an [`Observer`].
 pub fn button_small<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -80,6 +116,7 @@ where
 {
     button_base(
         text,
+        BUTTON_FONT_SIZE,
         action,
         Node {
             width: px(30),
@@ -91,9 +128,104 @@ where
     )
 }
 
+/// A round button big enough for a fingertip, with an icon-like text and an action defined as an [`Observer`].
+/// Screen readers read out `name` rather than the icon.
+pub fn button_icon<E, B, M, I>(text: impl Into<String>, name: &str, action: I) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        BUTTON_FONT_SIZE,
+        action,
+        (
+            AccessibleName(name.to_string()),
+            Node {
+                width: px(64),
+                height: px(64),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BorderRadius::MAX,
+        ),
+    )
+}
+
+/// A horizontal slider starting at `value` (in `0..=1`), with an action
+/// defined as an [`Observer`] of [`SliderChanged`].
+pub fn slider<B, M, I>(value: f32, action: I) -> impl Bundle
+where
+    B: Bundle,
+    I: IntoObserverSystem<SliderChanged, B, M>,
+{
+    let value = value.clamp(0.0, 1.0);
+    let action = IntoObserverSystem::into_system(action);
+    (
+        Name::new("Slider"),
+        Node::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent
+                .spawn((
+                    Name::new("Slider Track"),
+                    Slider { value },
+                    accessibility_node(value),
+                    Node {
+                        width: px(200),
+                        height: px(30),
+                        ..default()
+                    },
+                    children![
+                        (
+                            Name::new("Slider Bar"),
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: px(0),
+                                right: px(0),
+                                top: px(12),
+                                height: px(6),
+                                ..default()
+                            },
+                            BackgroundColor(SLIDER_TRACK),
+                            ThemeColor::SliderTrack,
+                            BorderRadius::MAX,
+                            Pickable::IGNORE,
+                        ),
+                        (
+                            Name::new("Slider Thumb"),
+                            SliderThumb,
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: percent(value * 100.0),
+                                top: px(5),
+                                width: px(20),
+                                height: px(20),
+                                // Centre the thumb on its value.
+                                margin: UiRect::left(px(-10)),
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_BACKGROUND),
+                            ThemeColor::ButtonBackground,
+                            BorderRadius::MAX,
+                            Pickable::IGNORE,
+                        ),
+                    ],
+                ))
+                .observe(press_slider)
+                .observe(drag_slider)
+                .observe(action);
+        })),
+    )
+}
+
+const BUTTON_FONT_SIZE: f32 = 40.0;
+
 /// A simple button with text and an action defined as an [`Observer`]. The button's layout is provided by `button_bundle`.
 fn button_base<E, B, M, I>(
     text: impl Into<String>,
+    font_size: f32,
     action: I,
     button_bundle: impl Bundle,
 ) -> impl Bundle
@@ -107,7 +239,7 @@ where
     (
         Name::new("Button"),
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent
                 .spawn((
                     Name::new("Button Inner"),
@@ -121,8 +253,9 @@ where
                     children![(
                         Name::new("Button Text"),
                         Text(text),
-                        TextFont::from_font_size(40.0),
+                        TextFont::from_font_size(font_size),
                         TextColor(BUTTON_TEXT),
+                        ThemeColor::ButtonText,
                         // Don't bubble picking events from the text up to the button.
                         Pickable::IGNORE,
                     )],